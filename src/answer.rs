@@ -0,0 +1,117 @@
+use std::fmt::Display;
+
+/// The answer to one part of a puzzle. Integers compare by value regardless
+/// of signedness, so `Signed(5) == Unsigned(5)`, and text compares by how
+/// both sides display, so `Text("5") == Unsigned(5)`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+}
+impl Answer {
+    /// Reads an answer back from its `Display` form, preferring integers
+    /// when they display the same way, so `007` stays text.
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        match (s.parse::<u64>(), s.parse::<i64>()) {
+            (Ok(n), _) if n.to_string() == s => Answer::Unsigned(n),
+            (_, Ok(n)) if n.to_string() == s => Answer::Signed(n),
+            _ => Answer::Text(s.to_string()),
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Signed(n) => Some(*n as i128),
+            Answer::Unsigned(n) => Some(*n as i128),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+impl Eq for Answer {}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Signed(n) => write!(f, "{n}"),
+            Answer::Unsigned(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($variant:ident, $target:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+impl_from!(Signed, i64, i8, i16, i32, i64, isize);
+impl_from!(Unsigned, u64, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::from(5_i32), Answer::from(5_u64));
+        assert_ne!(Answer::from(-1_i64), Answer::from(u64::MAX));
+        assert_eq!(Answer::from(5_u8), Answer::from("5"));
+        assert_ne!(Answer::from(7_u8), Answer::from("007"));
+        assert_eq!(Answer::from("ABC"), Answer::Text("ABC".to_string()));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Answer::parse("18446744073709551615"),
+            Answer::from(u64::MAX)
+        );
+        assert_eq!(Answer::parse("-3"), Answer::from(-3_i32));
+        assert_eq!(Answer::parse(" 42\n"), Answer::from(42_usize));
+        assert!(matches!(Answer::parse("EGJ"), Answer::Text(_)));
+        assert!(matches!(Answer::parse("007"), Answer::Text(_)));
+        assert!(matches!(Answer::parse("+5"), Answer::Text(_)));
+        assert!(matches!(Answer::parse("-0"), Answer::Text(_)));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for answer in [
+            Answer::from(-7_i32),
+            Answer::from(7_u32),
+            Answer::from("x,y"),
+            Answer::from("007"),
+        ] {
+            assert_eq!(Answer::parse(&answer.to_string()), answer);
+        }
+    }
+}
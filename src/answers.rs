@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::answer::Answer;
+use crate::solution::Part;
+
+/// File in the input root holding the confirmed answers, since they belong to
+/// the inputs they were computed from.
+pub const ANSWERS: &str = "answers";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(PathBuf, std::io::Error),
+    Malformed(PathBuf, usize),
+}
+impl Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            StoreError::Malformed(path, line) => {
+                write!(
+                    f,
+                    "{}, line {line}: expected `dayN partN <answer>`",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+/// Confirmed answers, stored one per line as `day5 part2 46`.
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<(u8, Part), Answer>,
+}
+impl AnswerStore {
+    pub fn open(root: &Path) -> Result<Self, StoreError> {
+        let path = root.join(ANSWERS);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(StoreError::Io(path, e)),
+        };
+
+        let mut answers = BTreeMap::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (key, answer) =
+                parse_line(line).ok_or(StoreError::Malformed(path.clone(), i + 1))?;
+            answers.insert(key, answer);
+        }
+        Ok(Self { path, answers })
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn record(&mut self, day: u8, part: Part, answer: Answer) {
+        self.answers.insert((day, part), answer);
+    }
+
+    pub fn save(&self) -> Result<(), StoreError> {
+        let contents = self
+            .answers
+            .iter()
+            .map(|((day, part), answer)| format!("day{day} part{} {answer}\n", part_number(*part)))
+            .collect::<String>();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| StoreError::Io(self.path.clone(), e))?;
+        }
+        std::fs::write(&self.path, contents).map_err(|e| StoreError::Io(self.path.clone(), e))
+    }
+}
+
+fn part_number(part: Part) -> u8 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+fn parse_line(line: &str) -> Option<((u8, Part), Answer)> {
+    let mut fields = line.splitn(3, ' ');
+    let day = fields.next()?.strip_prefix("day")?.parse::<u8>().ok()?;
+    let part = match fields.next()? {
+        "part1" => Part::One,
+        "part2" => Part::Two,
+        _ => return None,
+    };
+    let answer = Answer::parse(fields.next()?);
+    Some(((day, part), answer))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    Missing,
+}
+
+pub fn check(expected: Option<&Answer>, actual: Answer) -> Verdict {
+    match expected {
+        None => Verdict::Missing,
+        Some(expected) if *expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail {
+            expected: expected.clone(),
+            actual,
+        },
+    }
+}
+
+/// Describes how the actual answer differs from the expected one: the
+/// difference for integers, and the first differing position for text.
+pub fn diff(expected: &Answer, actual: &Answer) -> String {
+    if let (Some(e), Some(a)) = (expected.as_integer(), actual.as_integer()) {
+        return format!("expected {expected}, got {actual} ({:+})", a - e);
+    }
+
+    let (expected, actual) = (expected.to_string(), actual.to_string());
+    let position = expected
+        .chars()
+        .zip(actual.chars())
+        .position(|(e, a)| e != a)
+        .unwrap_or(expected.chars().count().min(actual.chars().count()));
+    format!("expected {expected}, got {actual} (differs from character {position})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = TempDir::new("answers");
+        let root = dir.path();
+
+        let mut store = AnswerStore::open(root).unwrap();
+        assert_eq!(store.get(5, Part::One), None);
+        store.record(5, Part::One, Answer::from(35_u64));
+        store.record(9, Part::Two, Answer::from(-2_i64));
+        store.record(1, Part::One, Answer::from("two words"));
+        store.save().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join(ANSWERS)).unwrap(),
+            "day1 part1 two words\nday5 part1 35\nday9 part2 -2\n"
+        );
+
+        let store = AnswerStore::open(root).unwrap();
+        assert_eq!(store.get(5, Part::One), Some(&Answer::from(35_u32)));
+        assert_eq!(store.get(9, Part::Two), Some(&Answer::from(-2_i32)));
+        assert_eq!(store.get(1, Part::One), Some(&Answer::from("two words")));
+
+        std::fs::write(root.join(ANSWERS), "day1 part3 4\n").unwrap();
+        assert!(matches!(
+            AnswerStore::open(root),
+            Err(StoreError::Malformed(_, 1))
+        ));
+    }
+
+    #[test]
+    fn test_check() {
+        let expected = Answer::from(46_u64);
+        assert_eq!(check(Some(&expected), Answer::from(46_i32)), Verdict::Pass);
+        assert_eq!(check(None, Answer::from(46_i32)), Verdict::Missing);
+        assert_eq!(
+            check(Some(&expected), Answer::from(47_u64)),
+            Verdict::Fail {
+                expected: Answer::from(46_u64),
+                actual: Answer::from(47_u64)
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(
+            diff(&Answer::from(46_u64), &Answer::from(40_u64)),
+            "expected 46, got 40 (-6)"
+        );
+        assert_eq!(
+            diff(&Answer::from("ABCD"), &Answer::from("ABXD")),
+            "expected ABCD, got ABXD (differs from character 2)"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default location of the saved baseline. Timings are machine specific, so
+/// the file is not checked in.
+pub const BASELINE: &str = ".bench-baseline";
+/// A phase counts as regressed if its median is this much slower than the
+/// baseline median.
+pub const REGRESSION_THRESHOLD: f64 = 0.2;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Phase {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+impl Phase {
+    fn key(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::PartOne => "part1",
+            Phase::PartTwo => "part2",
+        }
+    }
+}
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::PartOne => write!(f, "part one"),
+            Phase::PartTwo => write!(f, "part two"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Options {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stats {
+    pub median: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+impl Stats {
+    /// Summarizes a set of samples, `None` if there are none.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Some(Self {
+            median,
+            min: samples[0],
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Calls `f` `warmup` times without timing it, then times `iterations` calls.
+pub fn measure<F: FnMut()>(options: Options, mut f: F) -> Option<Stats> {
+    for _ in 0..options.warmup {
+        f();
+    }
+    let samples = (0..options.iterations)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let micros = d.as_secs_f64() * 1e6;
+    if micros < 1e3 {
+        format!("{micros:.1}µs")
+    } else if micros < 1e6 {
+        format!("{:.2}ms", micros / 1e3)
+    } else {
+        format!("{:.2}s", micros / 1e6)
+    }
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(PathBuf, std::io::Error),
+    Malformed(PathBuf, usize),
+}
+impl Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BaselineError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            BaselineError::Malformed(path, line) => write!(
+                f,
+                "{}, line {line}: expected `dayN <phase> <nanoseconds>`",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Median timings from an earlier run, stored one per line as
+/// `day5 part2 123456` in nanoseconds.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, Phase), Duration>,
+}
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(BaselineError::Io(path.to_path_buf(), e)),
+        };
+        let mut medians = BTreeMap::new();
+        for (i, line) in contents.lines().enumerate() {
+            let (day, phase, median) =
+                parse_line(line).ok_or(BaselineError::Malformed(path.to_path_buf(), i + 1))?;
+            medians.insert((day, phase), median);
+        }
+        Ok(Self { medians })
+    }
+
+    pub fn get(&self, day: u8, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(day, phase)).copied()
+    }
+
+    pub fn set(&mut self, day: u8, phase: Phase, median: Duration) {
+        self.medians.insert((day, phase), median);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        let contents = self
+            .medians
+            .iter()
+            .map(|((day, phase), d)| format!("day{day} {} {}\n", phase.key(), d.as_nanos()))
+            .collect::<String>();
+        std::fs::write(path, contents).map_err(|e| BaselineError::Io(path.to_path_buf(), e))
+    }
+}
+
+fn parse_line(line: &str) -> Option<(u8, Phase, Duration)> {
+    let mut fields = line.split_whitespace();
+    let day = fields.next()?.strip_prefix("day")?.parse::<u8>().ok()?;
+    let phase = match fields.next()? {
+        "parse" => Phase::Parse,
+        "part1" => Phase::PartOne,
+        "part2" => Phase::PartTwo,
+        _ => return None,
+    };
+    let nanos = fields.next()?.parse::<u64>().ok()?;
+    Some((day, phase, Duration::from_nanos(nanos)))
+}
+
+/// Relative change of `median` against `baseline`, e.g. `0.25` for 25% slower.
+pub fn change(median: Duration, baseline: Duration) -> f64 {
+    median.as_secs_f64() / baseline.as_secs_f64() - 1.0
+}
+
+pub fn is_regression(median: Duration, baseline: Duration) -> bool {
+    change(median, baseline) > REGRESSION_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(Stats::from_samples(vec![]), None);
+        assert_eq!(
+            Stats::from_samples(vec![ms(5), ms(1), ms(3)]),
+            Some(Stats {
+                median: ms(3),
+                min: ms(1),
+                max: ms(5)
+            })
+        );
+        assert_eq!(
+            Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(8)]).map(|s| s.median),
+            Some(ms(3))
+        );
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let options = Options {
+            warmup: 2,
+            iterations: 3,
+        };
+        assert!(measure(options, || calls += 1).is_some());
+        assert_eq!(calls, 5);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(1500)), "1.5µs");
+        assert_eq!(format_duration(Duration::from_micros(2500)), "2.50ms");
+        assert_eq!(format_duration(Duration::from_millis(3100)), "3.10s");
+    }
+
+    #[test]
+    fn test_baseline() {
+        let dir = TempDir::new("baseline");
+        let path = dir.join(BASELINE);
+        let mut baseline = Baseline::load(&path).unwrap();
+        assert_eq!(baseline.get(5, Phase::Parse), None);
+        baseline.set(5, Phase::Parse, Duration::from_nanos(1234));
+        baseline.set(5, Phase::PartTwo, ms(2));
+        baseline.save(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "day5 parse 1234\nday5 part2 2000000\n"
+        );
+
+        let baseline = Baseline::load(&path).unwrap();
+        assert_eq!(baseline.get(5, Phase::PartTwo), Some(ms(2)));
+    }
+
+    #[test]
+    fn test_regression() {
+        assert!(is_regression(ms(13), ms(10)));
+        assert!(!is_regression(ms(11), ms(10)));
+        assert!(!is_regression(ms(5), ms(10)));
+    }
+}
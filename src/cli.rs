@@ -0,0 +1,539 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::bench;
+use crate::registry;
+use crate::solution::{Part, Runnable};
+
+pub const USAGE: &str = "Usage: aoc2023 [--input <path>] [--input-dir <dir>] [--example] <command>
+
+Commands:
+  run [<day>] [--part 1|2]  Run a day, or the latest day if none is given
+  run --all                 Run every registered day
+      [--record]            Store the answers as confirmed, once the site accepted them
+  verify                    Run every day and compare with the recorded answers
+  list                      List the registered days
+  test <day>                Check a day against the examples from the puzzle text
+  bench <day> [--part 1|2]  Time the parser and each part of a day
+      [--warmup <n>]        Untimed runs before measuring, 1 by default
+      [--iterations <n>]    Timed runs, 10 by default
+      [--baseline <path>]   Baseline to compare with, .bench-baseline by default
+      [--save-baseline]     Store the medians of this run as the new baseline
+  inputs list               Show which days have a valid cached input
+  inputs fetch <day>        Download the input for a day, --force replaces a valid one
+  inputs fetch --all        Download the input for every registered day that is missing it
+  new <day>                 Add a new day from the template and register it
+      [--title <title>]     Puzzle title
+      [--example-input <path>]
+                            Example input to put in the test skeleton, - for stdin
+  help                      Print this message
+
+Options:
+  --input <path>            Read the puzzle input from <path>, or from stdin if <path> is -
+  --input-dir <dir>         Look for dayN or dayN.txt in <dir> instead of input/, can
+                            also be set with AOC_INPUT_DIR
+  --example                 Use the example input from the puzzle text";
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownFlag(String),
+    UnexpectedArgument(String),
+    MissingValue(&'static str),
+    MissingDay(&'static str),
+    InvalidDay(String),
+    UnknownDay(u8),
+    InvalidPart(String),
+    ConflictingFlags(&'static str, &'static str),
+    UnknownAction(String),
+    InvalidCount(String),
+}
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::UnknownCommand(c) => write!(f, "unknown command `{c}`"),
+            CliError::UnknownFlag(flag) => write!(f, "unknown flag `{flag}`"),
+            CliError::UnexpectedArgument(a) => write!(f, "unexpected argument `{a}`"),
+            CliError::MissingValue(flag) => write!(f, "`{flag}` needs a value"),
+            CliError::MissingDay(c) => write!(f, "`{c}` needs a day"),
+            CliError::InvalidDay(d) => write!(f, "`{d}` is not a day, expected 1-25"),
+            CliError::UnknownDay(d) => {
+                let days = registry::all()
+                    .iter()
+                    .map(|s| s.day().to_string())
+                    .collect::<Vec<String>>();
+                write!(
+                    f,
+                    "no solution registered for day {d}, available days: {}",
+                    days.join(", ")
+                )
+            }
+            CliError::InvalidPart(p) => write!(f, "`{p}` is not a part, expected 1 or 2"),
+            CliError::ConflictingFlags(a, b) => write!(f, "`{a}` can't be used with `{b}`"),
+            CliError::UnknownAction(a) => write!(f, "unknown inputs action `{a}`"),
+            CliError::InvalidCount(n) => write!(f, "`{n}` is not a count"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Target {
+    Latest,
+    Day(&'static dyn Runnable),
+    All,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InputsCommand {
+    List,
+    Fetch { day: Option<u8>, force: bool },
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Run {
+        target: Target,
+        part: Option<Part>,
+        record: bool,
+    },
+    Verify,
+    List,
+    Test(&'static dyn Runnable),
+    Bench {
+        solution: &'static dyn Runnable,
+        part: Option<Part>,
+        options: bench::Options,
+        baseline: PathBuf,
+        save_baseline: bool,
+    },
+    Inputs(InputsCommand),
+    New {
+        day: u8,
+        title: Option<String>,
+        example: Option<PathBuf>,
+    },
+    Help,
+}
+
+/// Where the puzzle input is read from.
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Default,
+    Path(PathBuf),
+    Example,
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub source: Source,
+    pub input_dir: Option<PathBuf>,
+    pub command: Command,
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Args, CliError> {
+    let mut input = None;
+    let mut input_dir = None;
+    let mut example = false;
+    let mut part = None;
+    let mut all = false;
+    let mut force = false;
+    let mut record = false;
+    let mut title = None;
+    let mut example_input = None;
+    let mut options = bench::Options::default();
+    let mut baseline = PathBuf::from(bench::BASELINE);
+    let mut save_baseline = false;
+    let mut positional = vec![];
+
+    // Asking for help wins over anything else on the command line, even
+    // arguments that wouldn't parse
+    let args = args.into_iter().collect::<Vec<String>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Args {
+            source: Source::Default,
+            input_dir: None,
+            command: Command::Help,
+        });
+    }
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(args.next().ok_or(CliError::MissingValue("--input"))?),
+            "--input-dir" => {
+                let dir = args.next().ok_or(CliError::MissingValue("--input-dir"))?;
+                input_dir = Some(PathBuf::from(dir));
+            }
+            "--example" => example = true,
+            "--part" => {
+                let p = args.next().ok_or(CliError::MissingValue("--part"))?;
+                part = Some(parse_part(&p)?);
+            }
+            "--all" => all = true,
+            "--force" => force = true,
+            "--record" => record = true,
+            "--title" => title = Some(args.next().ok_or(CliError::MissingValue("--title"))?),
+            "--warmup" => {
+                let n = args.next().ok_or(CliError::MissingValue("--warmup"))?;
+                options.warmup = parse_count(&n)?;
+            }
+            "--iterations" => {
+                let n = args.next().ok_or(CliError::MissingValue("--iterations"))?;
+                options.iterations = parse_count(&n)?.max(1);
+            }
+            "--baseline" => {
+                let path = args.next().ok_or(CliError::MissingValue("--baseline"))?;
+                baseline = PathBuf::from(path);
+            }
+            "--save-baseline" => save_baseline = true,
+            "--example-input" => {
+                let path = args
+                    .next()
+                    .ok_or(CliError::MissingValue("--example-input"))?;
+                example_input = Some(PathBuf::from(path));
+            }
+            flag if flag.starts_with('-') => return Err(CliError::UnknownFlag(arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let source = match (input, example) {
+        (Some(_), true) => return Err(CliError::ConflictingFlags("--input", "--example")),
+        (Some(path), false) => Source::Path(PathBuf::from(path)),
+        (None, true) => Source::Example,
+        (None, false) => Source::Default,
+    };
+
+    // Recorded answers are checked against the cached inputs, so they can
+    // only come from those
+    match (&source, record) {
+        (Source::Example, true) => return Err(CliError::ConflictingFlags("--record", "--example")),
+        (Source::Path(_), true) => return Err(CliError::ConflictingFlags("--record", "--input")),
+        _ => {}
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match positional.next().as_deref() {
+        None => Command::Run {
+            target: if all { Target::All } else { Target::Latest },
+            part,
+            record,
+        },
+        Some("run") => {
+            let target = match (positional.next(), all) {
+                (Some(_), true) => return Err(CliError::ConflictingFlags("<day>", "--all")),
+                (Some(day), false) => Target::Day(parse_day(&day)?),
+                (None, true) => Target::All,
+                (None, false) => Target::Latest,
+            };
+            Command::Run {
+                target,
+                part,
+                record,
+            }
+        }
+        Some("verify") => Command::Verify,
+        Some("list") => Command::List,
+        Some("test") => Command::Test(parse_day(
+            &positional.next().ok_or(CliError::MissingDay("test"))?,
+        )?),
+        Some("bench") => Command::Bench {
+            solution: parse_day(&positional.next().ok_or(CliError::MissingDay("bench"))?)?,
+            part,
+            options,
+            baseline,
+            save_baseline,
+        },
+        Some("inputs") => match positional.next().as_deref() {
+            Some("list") => Command::Inputs(InputsCommand::List),
+            Some("fetch") => {
+                let day = match (positional.next(), all) {
+                    (Some(_), true) => return Err(CliError::ConflictingFlags("<day>", "--all")),
+                    (Some(day), false) => Some(parse_day_number(&day)?),
+                    (None, true) => None,
+                    (None, false) => return Err(CliError::MissingDay("inputs fetch")),
+                };
+                Command::Inputs(InputsCommand::Fetch { day, force })
+            }
+            Some(action) => return Err(CliError::UnknownAction(action.to_string())),
+            None => return Err(CliError::MissingValue("inputs")),
+        },
+        Some("new") => Command::New {
+            day: parse_day_number(&positional.next().ok_or(CliError::MissingDay("new"))?)?,
+            title,
+            example: example_input,
+        },
+        Some("help") => Command::Help,
+        // Running a day by number alone is kept from the old interface
+        Some(day) if day.chars().all(|c| c.is_ascii_digit()) => Command::Run {
+            target: Target::Day(parse_day(day)?),
+            part,
+            record,
+        },
+        Some(command) => return Err(CliError::UnknownCommand(command.to_string())),
+    };
+
+    if let Some(arg) = positional.next() {
+        return Err(CliError::UnexpectedArgument(arg));
+    }
+
+    Ok(Args {
+        source,
+        input_dir,
+        command,
+    })
+}
+
+fn parse_day_number(day: &str) -> Result<u8, CliError> {
+    day.parse::<u8>()
+        .ok()
+        .filter(|d| (1..=25).contains(d))
+        .ok_or_else(|| CliError::InvalidDay(day.to_string()))
+}
+
+fn parse_day(day: &str) -> Result<&'static dyn Runnable, CliError> {
+    let number = parse_day_number(day)?;
+    registry::find(number).ok_or(CliError::UnknownDay(number))
+}
+
+fn parse_count(n: &str) -> Result<usize, CliError> {
+    n.parse::<usize>()
+        .map_err(|_| CliError::InvalidCount(n.to_string()))
+}
+
+fn parse_part(part: &str) -> Result<Part, CliError> {
+    match part {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(CliError::InvalidPart(part.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Args, CliError> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_run() {
+        let args = parse("run 5 --part 2").unwrap();
+        assert_eq!(args.source, Source::Default);
+        match args.command {
+            Command::Run {
+                target: Target::Day(s),
+                part,
+                record,
+            } => {
+                assert_eq!(s.day(), 5);
+                assert_eq!(part, Some(Part::Two));
+                assert!(!record);
+            }
+            _ => panic!("expected run command"),
+        }
+
+        assert!(matches!(
+            parse("run --all").unwrap().command,
+            Command::Run {
+                target: Target::All,
+                part: None,
+                record: false
+            }
+        ));
+        assert!(matches!(
+            parse("").unwrap().command,
+            Command::Run {
+                target: Target::Latest,
+                part: None,
+                record: false
+            }
+        ));
+        assert!(matches!(
+            parse("8").unwrap().command,
+            Command::Run {
+                target: Target::Day(_),
+                part: None,
+                record: false
+            }
+        ));
+        assert!(matches!(
+            parse("run 8 --record").unwrap().command,
+            Command::Run { record: true, .. }
+        ));
+        assert!(matches!(parse("verify").unwrap().command, Command::Verify));
+    }
+
+    #[test]
+    fn test_sources() {
+        assert_eq!(parse("--example test 1").unwrap().source, Source::Example);
+        assert_eq!(
+            parse("run 1 --input foo").unwrap().source,
+            Source::Path(PathBuf::from("foo"))
+        );
+        assert_eq!(
+            parse("run 1 --input -").unwrap().source,
+            Source::Path(PathBuf::from("-"))
+        );
+        assert_eq!(
+            parse("--input-dir foo run 1").unwrap().input_dir,
+            Some(PathBuf::from("foo"))
+        );
+        assert_eq!(
+            parse("run 1 --record --example").err(),
+            Some(CliError::ConflictingFlags("--record", "--example"))
+        );
+        assert_eq!(
+            parse("run 1 --record --input foo").err(),
+            Some(CliError::ConflictingFlags("--record", "--input"))
+        );
+        assert_eq!(
+            parse("run 1 --record --input -").err(),
+            Some(CliError::ConflictingFlags("--record", "--input"))
+        );
+        assert_eq!(
+            parse("run 1 --input foo --example").err(),
+            Some(CliError::ConflictingFlags("--input", "--example"))
+        );
+    }
+
+    #[test]
+    fn test_inputs() {
+        assert!(matches!(
+            parse("inputs list").unwrap().command,
+            Command::Inputs(InputsCommand::List)
+        ));
+        // Inputs can be fetched for days that have no solution yet
+        assert!(matches!(
+            parse("inputs fetch 25 --force").unwrap().command,
+            Command::Inputs(InputsCommand::Fetch {
+                day: Some(25),
+                force: true
+            })
+        ));
+        assert!(matches!(
+            parse("inputs fetch --all").unwrap().command,
+            Command::Inputs(InputsCommand::Fetch {
+                day: None,
+                force: false
+            })
+        ));
+        assert_eq!(
+            parse("inputs fetch").err(),
+            Some(CliError::MissingDay("inputs fetch"))
+        );
+        assert_eq!(
+            parse("inputs drop").err(),
+            Some(CliError::UnknownAction("drop".into()))
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        match parse("new 11 --title Cosmic --example-input -")
+            .unwrap()
+            .command
+        {
+            Command::New {
+                day,
+                title,
+                example,
+            } => {
+                assert_eq!(day, 11);
+                assert_eq!(title.as_deref(), Some("Cosmic"));
+                assert_eq!(example, Some(PathBuf::from("-")));
+            }
+            _ => panic!("expected new command"),
+        }
+        assert_eq!(parse("new").err(), Some(CliError::MissingDay("new")));
+    }
+
+    #[test]
+    fn test_bench() {
+        match parse("bench 5 --part 1 --iterations 3 --warmup 0 --save-baseline")
+            .unwrap()
+            .command
+        {
+            Command::Bench {
+                solution,
+                part,
+                options,
+                baseline,
+                save_baseline,
+            } => {
+                assert_eq!(solution.day(), 5);
+                assert_eq!(part, Some(Part::One));
+                assert_eq!(
+                    options,
+                    bench::Options {
+                        warmup: 0,
+                        iterations: 3
+                    }
+                );
+                assert_eq!(baseline, PathBuf::from(bench::BASELINE));
+                assert!(save_baseline);
+            }
+            _ => panic!("expected bench command"),
+        }
+        assert_eq!(
+            parse("bench 5 --iterations many").err(),
+            Some(CliError::InvalidCount("many".into()))
+        );
+    }
+
+    #[test]
+    fn test_help() {
+        for args in [
+            "help",
+            "--help",
+            "-h",
+            "run --help",
+            "run 5 --part 2 -h",
+            "bench --help",
+            "run 0 --bogus --help",
+            "--input foo --example --help",
+        ] {
+            assert!(
+                matches!(parse(args).unwrap().command, Command::Help),
+                "{args}"
+            );
+        }
+        assert_eq!(
+            parse("help run").err(),
+            Some(CliError::UnexpectedArgument("run".into()))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("run 0").err(), Some(CliError::InvalidDay("0".into())));
+        assert_eq!(
+            parse("run 26").err(),
+            Some(CliError::InvalidDay("26".into()))
+        );
+        assert_eq!(parse("run x").err(), Some(CliError::InvalidDay("x".into())));
+        assert_eq!(parse("run 25").err(), Some(CliError::UnknownDay(25)));
+        assert_eq!(
+            parse("run 1 --part 3").err(),
+            Some(CliError::InvalidPart("3".into()))
+        );
+        assert_eq!(
+            parse("run 1 --part").err(),
+            Some(CliError::MissingValue("--part"))
+        );
+        assert_eq!(parse("bench").err(), Some(CliError::MissingDay("bench")));
+        assert_eq!(
+            parse("fly").err(),
+            Some(CliError::UnknownCommand("fly".into()))
+        );
+        assert_eq!(
+            parse("list 3").err(),
+            Some(CliError::UnexpectedArgument("3".into()))
+        );
+        assert_eq!(
+            parse("run -v").err(),
+            Some(CliError::UnknownFlag("-v".into()))
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::math;
+
+/// The path of something stepping through a finite number of states, which
+/// must eventually repeat. After `start` steps it enters a cycle of `length`
+/// steps, and `hits` holds every step, up to the end of the first time round
+/// the cycle, on which it was somewhere of interest.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Orbit {
+    pub start: u64,
+    pub length: u64,
+    pub hits: Vec<u64>,
+}
+impl Orbit {
+    /// Follows `step` from `state` until a state comes round again, noting
+    /// the steps where `is_hit` holds.
+    pub fn trace<S, F, P>(mut state: S, mut step: F, mut is_hit: P) -> Self
+    where
+        S: Hash + Eq + Clone,
+        F: FnMut(&S) -> S,
+        P: FnMut(&S) -> bool,
+    {
+        let mut seen = HashMap::new();
+        let mut hits = vec![];
+        let mut t = 0;
+        while !seen.contains_key(&state) {
+            seen.insert(state.clone(), t);
+            if is_hit(&state) {
+                hits.push(t);
+            }
+            state = step(&state);
+            t += 1;
+        }
+        let start = seen[&state];
+        Self {
+            start,
+            length: t - start,
+            hits,
+        }
+    }
+
+    /// Whether step `t` is a hit, however far along it is.
+    pub fn hits_at(&self, t: u64) -> bool {
+        let t = if t < self.start {
+            t
+        } else {
+            self.start + (t - self.start) % self.length
+        };
+        self.hits.binary_search(&t).is_ok()
+    }
+}
+
+/// The first step on which every orbit is at a hit.
+pub fn first_common(orbits: &[Orbit]) -> Option<u64> {
+    let (first, rest) = orbits.split_first()?;
+    // Before every orbit has entered its cycle there are only so many steps
+    // to try
+    let settled = orbits.iter().map(|o| o.start).max()?;
+    let mut early = vec![];
+    for &hit in first.hits.iter() {
+        if hit < first.start {
+            early.push(hit);
+        } else {
+            early.extend((hit..settled).step_by(first.length as usize));
+        }
+    }
+    early.sort_unstable();
+    if let Some(&t) = early.iter().find(|&&t| rest.iter().all(|o| o.hits_at(t))) {
+        return Some(t);
+    }
+
+    // From then on each orbit hits on some residues of its cycle length, and
+    // every combination of those residues gives a sequence of common hits
+    let mut combined = vec![(0, 1)];
+    for orbit in orbits {
+        let residues = orbit
+            .hits
+            .iter()
+            .filter(|&&h| h >= orbit.start)
+            .map(|&h| (h % orbit.length) as i128)
+            .collect::<Vec<i128>>();
+        combined = combined
+            .iter()
+            .flat_map(|&c| {
+                residues
+                    .iter()
+                    .filter_map(move |&r| crt(c, (r, orbit.length as i128)))
+            })
+            .collect();
+    }
+    let settled = settled as i128;
+    combined
+        .into_iter()
+        .map(|(r, m)| r + (settled - r + m - 1).div_euclid(m) * m)
+        .min()
+        .and_then(|t| u64::try_from(t).ok())
+}
+
+/// Combines `x ≡ a (mod m)` and `x ≡ b (mod n)` into a single congruence,
+/// `x ≡ c (mod lcm(m, n))`, if there are any solutions. The moduli don't need
+/// to be coprime. Also `None` if the combined modulus doesn't fit.
+pub fn crt((a, m): (i128, i128), (b, n): (i128, i128)) -> Option<(i128, i128)> {
+    let (g, p, _) = math::ext_gcd(m, n);
+    if (b - a) % g != 0 {
+        return None;
+    }
+    let lcm = math::lcm(m, n)?;
+    // `p * m ≡ g (mod n)`, so stepping `a` on by multiples of `m` reaches `b`
+    let k = ((b - a) / g * p).rem_euclid(n / g);
+    Some(((a + k * m).rem_euclid(lcm), lcm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2 -> ...
+        let orbit = Orbit::trace(0, |&s| if s == 4 { 2 } else { s + 1 }, |&s| s % 2 == 0);
+        assert_eq!(
+            orbit,
+            Orbit {
+                start: 2,
+                length: 3,
+                hits: vec![0, 2, 4]
+            }
+        );
+        assert!(orbit.hits_at(0));
+        assert!(!orbit.hits_at(1));
+        assert!(orbit.hits_at(5));
+        assert!(orbit.hits_at(7));
+        assert!(!orbit.hits_at(6));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        // Non-coprime moduli only agree on some residues
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+        assert_eq!(crt((0, 1), (5, 7)), Some((5, 7)));
+        let big = 1_000_000_007;
+        assert_eq!(crt((1, big), (0, 2)), Some((big + 1, 2 * big)));
+    }
+
+    #[test]
+    fn test_first_common() {
+        let orbit = |start, length, hits: &[u64]| Orbit {
+            start,
+            length,
+            hits: hits.to_vec(),
+        };
+        // Hits at 2, 4, 6, ... and at 1, 4, 7, ...
+        let a = orbit(1, 2, &[2]);
+        let b = orbit(1, 3, &[1]);
+        assert_eq!(first_common(&[a.clone(), b.clone()]), Some(4));
+        // A hit before the cycle starts counts too
+        let c = orbit(3, 5, &[1, 4]);
+        assert_eq!(first_common(&[c.clone(), b.clone()]), Some(1));
+        assert_eq!(first_common(&[a.clone(), c]), Some(4));
+        // Always odd against always even
+        let odd = orbit(0, 2, &[1]);
+        let even = orbit(0, 2, &[0]);
+        assert_eq!(first_common(&[odd, even]), None);
+        assert_eq!(first_common(&[]), None);
+        assert_eq!(first_common(&[a]), Some(2));
+    }
+}
@@ -1,31 +1,63 @@
-pub fn main() {
-    let input = std::fs::read_to_string("input/day1").expect("No input");
-    println!("First puzzle: {}", first_puzzle(&input));
-    println!("Second puzzle: {}", second_puzzle(&input));
+use crate::error::Error;
+use crate::solution::{Example, Part, Solution};
+
+pub struct Day1;
+impl Solution for Day1 {
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: FIRST_INPUT,
+            answer: "142",
+        },
+        Example {
+            part: Part::Two,
+            input: SECOND_INPUT,
+            answer: "461",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<u32, Error> {
+        Ok(first_puzzle(input))
+    }
+
+    fn part_two(&self, input: &str) -> Result<u32, Error> {
+        second_puzzle(input)
+    }
 }
 
 fn first_puzzle(input: &str) -> u32 {
     let mut acc = 0;
     let base = 10;
-    for line in input.lines().into_iter() {
+    for line in input.lines() {
         let digits = line
             .chars()
             .filter_map(|c| c.to_digit(base))
             .collect::<Vec<u32>>();
-        if digits.len() > 0 {
+        if !digits.is_empty() {
             acc += digits.first().unwrap() * 10 + digits.last().unwrap();
         }
     }
     acc
 }
 
-fn second_puzzle(input: &str) -> u32 {
+fn second_puzzle(input: &str) -> Result<u32, Error> {
     let mut acc = 0;
-    for line in input.lines() {
+    for (i, line) in input.lines().enumerate() {
         let found_numbers = parse_single_line(line);
-        acc += found_numbers.first().unwrap() * 10 + found_numbers.last().unwrap();
+        let (Some(first), Some(last)) = (found_numbers.first(), found_numbers.last()) else {
+            return Err(Error::Invalid {
+                line: i + 1,
+                reason: "no digits on this line",
+            });
+        };
+        acc += first * 10 + last;
     }
-    acc
+    Ok(acc)
 }
 
 fn parse_single_line(line: &str) -> Vec<u32> {
@@ -58,16 +90,12 @@ fn parse_single_line(line: &str) -> Vec<u32> {
     found_numbers
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const FIRST_INPUT: &str = "1abc2
+const FIRST_INPUT: &str = "1abc2
     pqr3stu8vwx
     a1b2c3d4e5f
     treb7uchet";
 
-    const SECOND_INPUT: &str = "two1nine
+const SECOND_INPUT: &str = "two1nine
     eightwothree
     abcone2threexyz
     xtwone3four
@@ -78,6 +106,10 @@ mod tests {
     sevenine
     oneight";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_first_input() {
         assert_eq!(first_puzzle(FIRST_INPUT), 142);
@@ -94,6 +126,13 @@ mod tests {
             parse_single_line("three2fiveonexrllxsvfive"),
             vec![3, 2, 5, 1, 5]
         );
-        assert_eq!(second_puzzle(SECOND_INPUT), 281 + 83 + 79 + 18);
+        assert_eq!(second_puzzle(SECOND_INPUT), Ok(281 + 83 + 79 + 18));
+        assert_eq!(
+            second_puzzle("1\nabc"),
+            Err(Error::Invalid {
+                line: 2,
+                reason: "no digits on this line"
+            })
+        );
     }
 }
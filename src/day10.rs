@@ -1,182 +1,228 @@
-use std::collections::VecDeque;
+use crate::error::Error;
+use crate::geometry;
+use crate::grid::Grid;
+use crate::pos::{Dir, Pos};
+use crate::search;
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
 
-pub fn main() {
-    let input = std::fs::read_to_string("input/day10").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
-}
+pub struct Day10;
+impl Solution for Day10 {
+    type PartOne = i32;
+    type PartTwo = i32;
 
-#[derive(Debug, PartialEq)]
-struct MapNode {
-    val: u8,
-    dist: i32,
-    interior: bool,
-}
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: MAP1,
+            answer: "4",
+        },
+        Example {
+            part: Part::Two,
+            input: MAP1,
+            answer: "1",
+        },
+        Example {
+            part: Part::Two,
+            input: MAP2,
+            answer: "4",
+        },
+        Example {
+            part: Part::Two,
+            input: MAP3,
+            answer: "10",
+        },
+        Example {
+            part: Part::Two,
+            input: MAP4,
+            answer: "4",
+        },
+    ];
 
-fn part_one(input: &str) -> i32 {
-    let rows = input.lines().count();
-    let columns = input.lines().nth(0).unwrap().len();
-    let mut map = create_map(rows, columns, input);
+    fn part_one(&self, input: &str) -> Result<i32, Error> {
+        part_one(input)
+    }
 
-    find_main_loop(&mut map, columns, rows);
+    fn part_two(&self, input: &str) -> Result<i32, Error> {
+        part_two(input)
+    }
 
-    map.iter()
-        .fold(0, |acc, map_node| acc.max(map_node.dist as i32))
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(Maze::parse(input)).map(drop))
+    }
 }
 
-fn find_main_loop(map: &mut Vec<MapNode>, columns: usize, rows: usize) {
-    let start = map
-        .iter()
-        .position(|node| node.val == b'S')
-        .expect("No valid start found");
-
-    let mut pos_to_visit = VecDeque::new();
-    pos_to_visit.push_back((start, 0));
-    while let Some((this_pos, dist)) = pos_to_visit.pop_front() {
-        if map[this_pos].dist > 0 {
-            // This node has already been handled
-            continue;
-        }
-
-        map[this_pos].dist = dist;
-        let dist = dist + 1;
+/// A pipe, named by the two directions it connects.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Pipe {
+    NS,
+    EW,
+    NE,
+    NW,
+    SW,
+    SE,
+}
+impl Pipe {
+    const ALL: [Pipe; 6] = [Pipe::NS, Pipe::EW, Pipe::NE, Pipe::NW, Pipe::SW, Pipe::SE];
 
-        if this_pos > columns {
-            let top = this_pos - columns;
-            match map[top].val {
-                b'|' | b'7' | b'F' => pos_to_visit.push_back((top, dist)),
-                _ => {}
-            };
-        }
-        if this_pos < columns * (rows - 1) {
-            let bottom = this_pos + columns;
-            match map[bottom].val {
-                b'|' | b'L' | b'J' => pos_to_visit.push_back((bottom, dist)),
-                _ => {}
-            }
-        }
-        if this_pos % columns > 0 {
-            let left = this_pos - 1;
-            match map[left].val {
-                b'-' | b'F' | b'L' => pos_to_visit.push_back((left, dist)),
-                _ => {}
-            }
-        }
-        if this_pos % columns < columns {
-            let right = this_pos + 1;
-            match map[right].val {
-                b'-' | b'J' | b'7' => pos_to_visit.push_back((right, dist)),
-                _ => {}
-            }
+    fn from_char(c: char) -> Option<Pipe> {
+        match c {
+            '|' => Some(Pipe::NS),
+            '-' => Some(Pipe::EW),
+            'L' => Some(Pipe::NE),
+            'J' => Some(Pipe::NW),
+            '7' => Some(Pipe::SW),
+            'F' => Some(Pipe::SE),
+            _ => None,
         }
     }
-}
 
-fn create_map(rows: usize, columns: usize, input: &str) -> Vec<MapNode> {
-    let mut map: Vec<MapNode> = Vec::with_capacity(rows * columns);
-    for line in input.lines() {
-        for byte in line.bytes() {
-            map.push(MapNode {
-                val: byte,
-                dist: 0,
-                interior: false,
-            });
+    /// The two directions the pipe leads out of its tile.
+    fn connections(self) -> [Dir; 2] {
+        match self {
+            Pipe::NS => [Dir::N, Dir::S],
+            Pipe::EW => [Dir::E, Dir::W],
+            Pipe::NE => [Dir::N, Dir::E],
+            Pipe::NW => [Dir::N, Dir::W],
+            Pipe::SW => [Dir::S, Dir::W],
+            Pipe::SE => [Dir::S, Dir::E],
         }
     }
-    map
+
+    fn connects(self, dir: Dir) -> bool {
+        self.connections().contains(&dir)
+    }
+
+    /// The pipe joining two directions, in either order.
+    fn joining(a: Dir, b: Dir) -> Option<Pipe> {
+        Pipe::ALL
+            .into_iter()
+            .find(|p| a != b && p.connects(a) && p.connects(b))
+    }
 }
 
-fn part_two(input: &str) -> i32 {
-    let rows = input.lines().count();
-    let columns = input.lines().nth(0).unwrap().len();
-    let mut map = create_map(rows, columns, input);
-
-    find_main_loop(&mut map, columns, rows);
-
-    // Replace the S
-    replace_start(&mut map, columns, rows);
-
-    let mut interior_points = 0;
-    for i in 0..columns {
-        let mut interior = false;
-        for j in 0..rows {
-            let index = columns * i + j;
-            if map[index].dist > 0 {
-                match map[index].val {
-                    b'-' | b'F' | b'L' | b'J' | b'7' => {
-                        // Check if this is part of the main loop
-                        if map[index].val > 0 {
-                            println!("Crossing boundary at {i}, {j}");
-                            interior = !interior;
-                        }
-                    }
-                    _ => {}
-                };
-            } else if interior {
-                println!("Adding {} at {i}, {j} as interior", map[index].val as char);
-                interior_points += 1;
-            }
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Tile {
+    Ground,
+    Start,
+    Pipe(Pipe),
+}
+impl Tile {
+    fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Ground),
+            'S' => Some(Tile::Start),
+            _ => Pipe::from_char(c).map(Tile::Pipe),
         }
     }
+}
 
-    interior_points
+/// The pipe maze, with the start tile replaced by the pipe it must be.
+struct Maze {
+    tiles: Grid<Tile>,
+    start: (usize, usize),
 }
+impl Maze {
+    fn parse(input: &str) -> Result<Self, Error> {
+        let mut tiles = Grid::parse(input, Tile::from_char)?;
+        let start = tiles
+            .position(|&t| t == Tile::Start)
+            .ok_or(Error::NoAnswer("no start tile `S`"))?;
+        let pipe = infer_start(&tiles, start).ok_or(Error::NoAnswer(
+            "the start tile doesn't connect to exactly two pipes",
+        ))?;
+        tiles[start] = Tile::Pipe(pipe);
+        Ok(Self { tiles, start })
+    }
 
-fn replace_start(map: &mut [MapNode], columns: usize, rows: usize) {
-    if let Some(i) = map.iter().position(|n| n.val == b'S') {
-        let mut top = false;
-        let mut bottom = false;
-        let mut left = false;
-        let mut right = false;
-        if i > columns {
-            top = matches!(map[i - columns].val, b'|' | b'7' | b'F');
-        }
-        if i < columns * (rows - 1) {
-            bottom = matches!(map[i - columns].val, b'|' | b'J' | b'L');
-        }
-        if i % columns > 0 {
-            left = matches!(map[i - columns].val, b'-' | b'F' | b'L');
-        }
-        if i % columns < columns {
-            right = matches!(map[i - columns].val, b'-' | b'J' | b'7');
+    fn pipe(&self, pos: (usize, usize)) -> Option<Pipe> {
+        match self.tiles.get(pos.0, pos.1)? {
+            Tile::Pipe(pipe) => Some(*pipe),
+            _ => None,
         }
+    }
 
-        if top {
-            if left {
-                map[i].val = b'L';
-            }
-            if right {
-                map[i].val = b'J';
-            }
-            if bottom {
-                map[i].val = b'|';
-            }
-        }
-        if left {
-            if right {
-                map[i].val = b'-';
-            }
-            if bottom {
-                map[i].val = b'7';
+    /// The tile one step from `pos` towards `dir`, if pipes on both tiles
+    /// lead into each other.
+    fn follow(&self, pos: (usize, usize), dir: Dir) -> Option<(usize, usize)> {
+        let next = self.tiles.step(pos, dir)?;
+        let joined = self.pipe(pos)?.connects(dir) && self.pipe(next)?.connects(dir.opposite());
+        joined.then_some(next)
+    }
+
+    /// The tiles the pipe at `pos` is joined to.
+    fn connected(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Dir::CARDINAL
+            .into_iter()
+            .filter_map(move |dir| self.follow(pos, dir))
+    }
+
+    /// The tiles of the loop through the start, in order along it and
+    /// starting with the start tile.
+    fn main_loop(&self) -> Result<Vec<(usize, usize)>, Error> {
+        let broken = Error::NoAnswer("the loop through the start tile is broken");
+        let mut pos = self.start;
+        let mut dir = self.pipe(pos).ok_or(broken.clone())?.connections()[0];
+        let mut tiles = vec![];
+        loop {
+            tiles.push(pos);
+            pos = self.follow(pos, dir).ok_or(broken.clone())?;
+            if pos == self.start {
+                return Ok(tiles);
             }
-        }
-        if bottom && right {
-            map[i].val = b'F';
+            let [a, b] = self.pipe(pos).ok_or(broken.clone())?.connections();
+            dir = if a == dir.opposite() { b } else { a };
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The pipe the start tile must be for its neighbours to lead into it. Only
+/// one pair of neighbours may.
+fn infer_start(tiles: &Grid<Tile>, start: (usize, usize)) -> Option<Pipe> {
+    let leads_back = Dir::CARDINAL
+        .into_iter()
+        .filter(|&dir| {
+            tiles.step(start, dir).is_some_and(
+                |next| matches!(tiles[next], Tile::Pipe(p) if p.connects(dir.opposite())),
+            )
+        })
+        .collect::<Vec<Dir>>();
+    match leads_back[..] {
+        [a, b] => Pipe::joining(a, b),
+        _ => None,
+    }
+}
 
-    const MAP1: &str = ".....
+fn part_one(input: &str) -> Result<i32, Error> {
+    let maze = Maze::parse(input)?;
+    // The farthest tile along the loop, in whichever direction is shorter
+    let distances = search::bfs(maze.start, |&pos| maze.connected(pos).collect::<Vec<_>>());
+    Ok(distances.distances().map(|(_, d)| d).max().unwrap_or(0) as i32)
+}
+
+/// The tiles enclosed by the loop are the lattice points strictly inside the
+/// polygon traced by its tile centres.
+fn part_two(input: &str) -> Result<i32, Error> {
+    let maze = Maze::parse(input)?;
+    let vertices = maze
+        .main_loop()?
+        .into_iter()
+        .map(Pos::from)
+        .collect::<Vec<Pos>>();
+    geometry::interior_points(&vertices)
+        .and_then(|n| i32::try_from(n).ok())
+        .ok_or(Error::NoAnswer("too many tiles are enclosed to count"))
+}
+
+const MAP1: &str = ".....
 .S-7.
 .|.|.
 .L-J.
 .....";
 
-    const MAP2: &str = "...........
+const MAP2: &str = "...........
 .S-------7.
 .|F-----7|.
 .||.....||.
@@ -186,7 +232,7 @@ mod tests {
 .L--J.L--J.
 ...........";
 
-    const MAP3: &str = "FF7FSF7F7F7F7F7F---7
+const MAP3: &str = "FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
 F--JF--7||LJLJ7F7FJ-
@@ -197,7 +243,7 @@ L---JF-JLJ.||-FJLJJ7
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L";
 
-    const MAP4: &str = "...........
+const MAP4: &str = "..........
 .S------7.
 .|F----7|.
 .||....||.
@@ -207,16 +253,61 @@ L7JLJL-JLJLJL--JLJ.L";
 .L--JL--J.
 ..........";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(MAP1), 4);
+        assert_eq!(part_one(MAP1), Ok(4));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(MAP1), 1);
-        assert_eq!(part_two(MAP2), 4);
-        assert_eq!(part_two(MAP3), 10);
-        assert_eq!(part_two(MAP4), 4);
+        assert_eq!(part_two(MAP1), Ok(1));
+        assert_eq!(part_two(MAP2), Ok(4));
+        assert_eq!(part_two(MAP3), Ok(10));
+        assert_eq!(part_two(MAP4), Ok(4));
+    }
+
+    #[test]
+    fn test_start_inference() {
+        let maze = Maze::parse(MAP1).unwrap();
+        assert_eq!(maze.start, (1, 1));
+        assert_eq!(maze.pipe(maze.start), Some(Pipe::SE));
+        let maze = Maze::parse(MAP3).unwrap();
+        assert_eq!(maze.pipe(maze.start), Some(Pipe::SW));
+        assert!(Maze::parse("...\n.S.\n...").is_err());
+        // Three neighbours lead into the start, so it's ambiguous
+        assert!(Maze::parse(".|.\n-S-\n...").is_err());
+    }
+
+    #[test]
+    fn test_main_loop() {
+        let maze = Maze::parse(MAP1).unwrap();
+        assert_eq!(
+            maze.main_loop(),
+            Ok(vec![
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (3, 3),
+                (2, 3),
+                (1, 3),
+                (1, 2)
+            ])
+        );
+        // Pipes off the loop are ignored
+        let maze = Maze::parse(MAP3).unwrap();
+        let main_loop = maze.main_loop().unwrap();
+        assert_eq!(main_loop.len() as i32, 2 * part_one(MAP3).unwrap());
+        assert_eq!(main_loop[0], maze.start);
+        assert!(main_loop
+            .iter()
+            .zip(main_loop.iter().cycle().skip(1))
+            .all(|(&a, &b)| Pos::from(a).manhattan(b.into()) == 1));
+        // A loop that never comes back to the start
+        assert!(Maze::parse("F7.\nS..\n|..").unwrap().main_loop().is_err());
     }
 }
@@ -1,7 +1,39 @@
-pub fn main() {
-    let input = std::fs::read_to_string("input/day2").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
+use crate::error::Error;
+use crate::parsing::{lines, ErrorKind, ParseError};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
+
+pub struct Day2;
+impl Solution for Day2 {
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "8",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "2286",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<i32, Error> {
+        part_one(input)
+    }
+
+    fn part_two(&self, input: &str) -> Result<i32, Error> {
+        part_two(input)
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(parse_input(input)).map(drop).map_err(Error::from))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -26,45 +58,30 @@ impl CubeSet {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Game> {
+fn parse_input(input: &str) -> Result<Vec<Game>, ParseError> {
     let mut output = vec![];
-    for game in input.lines() {
-        let split = game.split(':').collect::<Vec<&str>>();
-
-        let game_id = split.first().unwrap().trim();
-        let game_id = game_id
-            .rsplit_once(' ')
-            .map_or(0, |(_, digit)| digit.parse::<i32>().unwrap());
-
-        let mut game = Game {
-            id: game_id,
-            sets: vec![],
-        };
+    for line in lines(input) {
+        let (id, sets) = line.trim().split_once(':')?;
+        let [id] = id.array()?;
+        let mut game = Game { id, sets: vec![] };
 
-        let sets = split.last().unwrap().trim();
         for set in sets.split(';') {
-            let set = set.trim();
             let mut cube_set = CubeSet::new(0, 0, 0);
             for val in set.split(',') {
-                let val = val.trim();
-                let digit = val.split(' ').nth(0);
-                if let Some(digit) = digit.and_then(|s| s.parse::<i32>().ok()) {
-                    if val.ends_with("red") {
-                        cube_set.red = digit;
-                    }
-                    if val.ends_with("green") {
-                        cube_set.green = digit;
-                    }
-                    if val.ends_with("blue") {
-                        cube_set.blue = digit;
-                    }
+                let (digit, color) = val.trim().split_once(' ')?;
+                let digit = digit.integer()?;
+                match color.text.trim() {
+                    "red" => cube_set.red = digit,
+                    "green" => cube_set.green = digit,
+                    "blue" => cube_set.blue = digit,
+                    _ => return Err(color.error(ErrorKind::Unexpected(color.text.to_string()))),
                 }
             }
             game.sets.push(cube_set);
         }
         output.push(game);
     }
-    output
+    Ok(output)
 }
 
 #[allow(dead_code)]
@@ -72,7 +89,7 @@ fn parse_input_sscanf(input: &str) -> Option<Vec<Game>> {
     let mut output = vec![];
     for game in input.lines() {
         let mut split = game.split(':');
-        let game_id = split.nth(0)?.trim();
+        let game_id = split.next()?.trim();
         let game_id = sscanf::sscanf!(game_id, "Game {}", i32).ok()?;
 
         let mut game = Game {
@@ -101,8 +118,8 @@ fn parse_input_sscanf(input: &str) -> Option<Vec<Game>> {
     Some(output)
 }
 
-fn part_one(input: &str) -> i32 {
-    let input = parse_input(input);
+fn part_one(input: &str) -> Result<i32, Error> {
+    let input = parse_input(input)?;
     let max_red = 12;
     let max_green = 13;
     let max_blue = 14;
@@ -122,11 +139,11 @@ fn part_one(input: &str) -> i32 {
         }
         acc += game.id;
     }
-    acc
+    Ok(acc)
 }
 
-fn part_two(input: &str) -> i32 {
-    let input = parse_input(input);
+fn part_two(input: &str) -> Result<i32, Error> {
+    let input = parse_input(input)?;
     let mut acc = 0;
     for game in input {
         let mut red = 0;
@@ -139,23 +156,23 @@ fn part_two(input: &str) -> i32 {
         }
         acc += red * green * blue;
     }
-    acc
+    Ok(acc)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+const INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
    Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
    Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
    Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
    Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_parse_input() {
         assert_eq!(
-            parse_input(INPUT).first().unwrap(),
+            parse_input(INPUT).unwrap().first().unwrap(),
             &Game {
                 id: 1,
                 sets: vec![
@@ -166,7 +183,7 @@ mod tests {
             }
         );
         assert_eq!(
-            parse_input(INPUT).get(2).unwrap(),
+            parse_input(INPUT).unwrap().get(2).unwrap(),
             &Game {
                 id: 3,
                 sets: vec![
@@ -178,18 +195,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_input("Game 1: 3 blue\nGame 2: 4 purple").unwrap_err(),
+            ParseError {
+                line: 2,
+                column: 11,
+                kind: ErrorKind::Unexpected("purple".to_string())
+            }
+        );
+        assert_eq!(parse_input("Game x: 3 blue").unwrap_err().column, 1);
+    }
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT), 8);
+        assert_eq!(part_one(INPUT), Ok(8));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT), 2286);
+        assert_eq!(part_two(INPUT), Ok(2286));
     }
 
     #[test]
     fn test_sscanf() {
-        assert_eq!(parse_input(INPUT), parse_input_sscanf(INPUT).unwrap());
+        assert_eq!(
+            parse_input(INPUT).unwrap(),
+            parse_input_sscanf(INPUT).unwrap()
+        );
     }
 }
@@ -1,106 +1,130 @@
-use std::ops::Add;
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::pos::Pos;
+use crate::reach::Reachability;
+use crate::solution::Solution;
+use std::hint::black_box;
 
-pub fn main() {
-    let input = std::fs::read_to_string("input/day21").expect("No input");
-    println!("Part one: {}", part_one(&input, 64));
-    // println!("Part two: {}", part_two(&input));
-}
+pub struct Day21;
+impl Solution for Day21 {
+    type PartOne = i32;
+    type PartTwo = u64;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Pos {
-    r: i32,
-    c: i32,
-}
-impl Pos {
-    fn new(r: i32, c: i32) -> Self {
-        Self { r, c }
-    }
-}
-impl Add for Pos {
-    type Output = Pos;
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            r: self.r + rhs.r,
-            c: self.c + rhs.c,
-        }
+    const DAY: u8 = 21;
+    const TITLE: &'static str = "Step Counter";
+
+    fn part_one(&self, input: &str) -> Result<i32, Error> {
+        part_one(input, 64)
     }
-}
 
-fn part_one(input: &str, steps: usize) -> i32 {
-    let n_rows = input.lines().count();
-    let n_cols = input.lines().nth(0).unwrap().chars().count();
-
-    let mut start_pos = Pos { r: 0, c: 0 };
-    let mut map: Vec<char> = Vec::with_capacity(input.chars().count());
-    for (row, l) in input.lines().enumerate() {
-        for (col, c) in l.chars().enumerate() {
-            map.push(c);
-            if c == 'S' {
-                start_pos = Pos {
-                    r: row as i32,
-                    c: col as i32,
-                };
-            }
-        }
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input, 26501365)
     }
 
-    let mut maps = [map.clone(), map.clone()];
-    // Populate the starting pos of the first map
-    maps[0][get_index(start_pos, n_cols)] = 'O';
-
-    for i in 0..steps {
-        let read_map_index = i % 2;
-        let write_map_index = (i + 1) % 2;
-        maps[write_map_index] = map.clone();
-        for j in 0..map.len() {
-            if maps[read_map_index][j] == 'O' {
-                let pos = get_pos(j, n_cols);
-
-                let neighbors = [
-                    Pos::new(-1, 0),
-                    Pos::new(1, 0),
-                    Pos::new(0, -1),
-                    Pos::new(0, 1),
-                ];
-                for k in 0..neighbors.len() {
-                    let neighbor_pos = pos + neighbors[k];
-                    if pos.r > 0
-                        && pos.r < (n_rows as i32 - 1)
-                        && pos.c > 0
-                        && pos.c < (n_cols as i32 - 1)
-                    {
-                        let neighbor_index = get_index(neighbor_pos, n_cols);
-                        if maps[write_map_index][neighbor_index] != '#' {
-                            maps[write_map_index][neighbor_index] = 'O';
-                        }
-                    }
-                }
-            }
-        }
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(parse_map(input)).map(drop))
     }
+}
 
-    let last_map = &maps[steps % 2];
-    last_map.iter().filter(|&c| *c == 'O').count() as i32
+fn parse_map(input: &str) -> Result<(Grid<char>, (usize, usize)), Error> {
+    let map = Grid::parse(input, |c| matches!(c, '.' | '#' | 'S').then_some(c))?;
+    let start_pos = map
+        .position(|&c| c == 'S')
+        .ok_or(Error::NoAnswer("no start tile `S`"))?;
+    Ok((map, start_pos))
 }
 
-fn get_index(pos: Pos, row_length: usize) -> usize {
-    pos.r as usize * row_length + pos.c as usize
+fn part_one(input: &str, steps: usize) -> Result<i32, Error> {
+    let (map, start_pos) = parse_map(input)?;
+    let open = |row, col| map[(row, col)] != '#';
+    let reach = Reachability::new(map.width(), map.height(), open, start_pos);
+    Ok(reach.counts(steps)[steps] as i32)
 }
 
-fn get_pos(i: usize, row_length: usize) -> Pos {
-    Pos {
-        r: (i / row_length) as i32,
-        c: (i % row_length) as i32,
+fn part_two(input: &str, steps: usize) -> Result<u64, Error> {
+    let (map, start_pos) = parse_map(input)?;
+    count_reachable(&map, start_pos, steps).ok_or(Error::NoAnswer(
+        "the number of reachable plots never settled into quadratic growth",
+    ))
+}
+
+/// Number of consecutive equal second differences needed before the growth
+/// is trusted to be quadratic.
+const STABLE_DIFFERENCES: usize = 3;
+/// Samples to take before giving up on finding quadratic growth.
+const MAX_SAMPLES: usize = 8;
+
+/// Counts the plots reachable in exactly `steps` steps on the garden tiled
+/// endlessly in every direction.
+///
+/// Once the frontier has spread past the first few tiles, the count sampled
+/// every `period` steps grows quadratically, so the walk only runs until the
+/// second differences of those samples settle, and extrapolates from there.
+/// The period covers two tiles so that odd sized gardens line up with the
+/// parity of the step count.
+fn count_reachable(map: &Grid<char>, start: (usize, usize), steps: usize) -> Option<u64> {
+    let period = 2 * map.width().max(map.height());
+    let offset = steps % period;
+    let limit = steps.min(offset + period * (MAX_SAMPLES - 1));
+
+    // Enough copies of the garden around the start that the walk can't
+    // reach the edge within `limit` steps
+    let (rows, cols) = (limit.div_ceil(map.height()), limit.div_ceil(map.width()));
+    let tiled = map.tiled();
+    let open = |row, col| *tiled.get(Pos::from((row, col))) != '#';
+    let start = (start.0 + rows * map.height(), start.1 + cols * map.width());
+    let mut reach = Reachability::new(
+        map.width() * (2 * cols + 1),
+        map.height() * (2 * rows + 1),
+        open,
+        start,
+    );
+
+    let n = ((steps - offset) / period) as u64;
+    let mut samples = vec![];
+    for step in 0..=limit {
+        if step == steps {
+            return Some(reach.count());
+        }
+        if step >= offset && (step - offset).is_multiple_of(period) {
+            samples.push(reach.count());
+            if let Some(count) = extrapolate(&samples, n) {
+                return Some(count);
+            }
+        }
+        reach.step();
     }
+    None
 }
 
-fn part_two(input: &str) -> i32 {
-    0
+/// Extends `samples` to the `n`th term, once its last second differences
+/// have been the same `STABLE_DIFFERENCES` times in a row.
+fn extrapolate(samples: &[u64], n: u64) -> Option<u64> {
+    if samples.len() < STABLE_DIFFERENCES + 2 {
+        return None;
+    }
+    let samples = samples.iter().map(|&s| s as i128).collect::<Vec<i128>>();
+    let second = samples
+        .windows(3)
+        .map(|w| w[2] - 2 * w[1] + w[0])
+        .rev()
+        .take(STABLE_DIFFERENCES)
+        .collect::<Vec<i128>>();
+    if second.iter().any(|&d| d != second[0]) {
+        return None;
+    }
+
+    let last = samples.len() - 1;
+    let k = (n as i128) - last as i128;
+    let first = samples[last] - samples[last - 1];
+    let count = samples[last] + k * first + k * (k + 1) / 2 * second[0];
+    Some(count as u64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search;
 
     const INPUT: &str = "...........
 .....###.#.
@@ -116,14 +140,47 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(&INPUT, 6), 16);
+        assert_eq!(part_one(INPUT, 6), Ok(16));
+    }
+
+    #[test]
+    fn test_matches_bfs() {
+        // A plot is reachable in exactly `k` steps when its shortest path is no
+        // longer and has the same parity, as the walker can step back and forth
+        let (map, start) = parse_map(INPUT).unwrap();
+        let search = search::bfs(start, |&p| {
+            map.neighbours4(p)
+                .filter(|&n| map[n] != '#')
+                .collect::<Vec<_>>()
+        });
+        let open = |row, col| map[(row, col)] != '#';
+        let counts = Reachability::new(map.width(), map.height(), open, start).counts(20);
+        for (k, &count) in counts.iter().enumerate() {
+            let k = k as u64;
+            let expected = search
+                .distances()
+                .filter(|&(_, d)| d <= k && d % 2 == k % 2)
+                .count();
+            assert_eq!(count, expected as u64);
+        }
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(part_two(INPUT, 6), Ok(16));
+        assert_eq!(part_two(INPUT, 10), Ok(50));
+        assert_eq!(part_two(INPUT, 50), Ok(1594));
+        assert_eq!(part_two(INPUT, 100), Ok(6536));
+        assert_eq!(part_two(INPUT, 500), Ok(167004));
+        assert_eq!(part_two(INPUT, 1000), Ok(668697));
+        assert_eq!(part_two(INPUT, 5000), Ok(16733044));
     }
 
     #[test]
-    fn test_get_index() {
-        let index = get_index(Pos { r: 5, c: 5 }, 11);
-        assert_eq!(index, 60);
-        let pos = get_pos(index, 11);
-        assert_eq!(pos, Pos { r: 5, c: 5 });
+    fn test_extrapolate() {
+        let squares = [0, 1, 4, 9, 16];
+        assert_eq!(extrapolate(&squares[..4], 10), None);
+        assert_eq!(extrapolate(&squares, 10), Some(100));
+        assert_eq!(extrapolate(&[0, 1, 4, 9, 17], 10), None);
     }
 }
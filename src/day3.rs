@@ -1,191 +1,192 @@
-const COLS: usize = 140;
-const RANK: usize = COLS * COLS;
-
-pub fn main() {
-    let input = std::fs::read_to_string("input/day3.txt").expect("No input");
-    println!("Part one: {}", part_one(&input, COLS));
-    println!("Part two: {}", part_two(&input, COLS));
-}
-
-fn part_one(input: &str, size: usize) -> u32 {
-    let input_grid = to_grid(input);
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::parsing::{ErrorKind, ParseError};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
+
+pub struct Day3;
+impl Solution for Day3 {
+    type PartOne = u32;
+    type PartTwo = u64;
+
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "4361",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "467835",
+        },
+    ];
 
-    let mut acc = 0;
-    for i in (0..size).rev() {
-        let mut line_acc = 0;
-        let mut consecutive_number = 0;
-        let mut adjacent_symbol = false;
-        for j in (0..size).rev() {
-            let base: u32 = 10;
-            if let Some(digit) = input_grid[i][j].to_digit(base) {
-                line_acc += digit * base.pow(consecutive_number);
-                consecutive_number += 1;
-                adjacent_symbol |= has_adjacent_symbol(&input_grid, i, j);
-                if j > 0 {
-                    continue;
-                }
-            }
-            if adjacent_symbol {
-                acc += line_acc;
-            }
-            line_acc = 0;
-            consecutive_number = 0;
-            adjacent_symbol = false;
-        }
+    fn part_one(&self, input: &str) -> Result<u32, Error> {
+        part_one(input)
     }
-    acc
-}
 
-fn to_grid(input: &str) -> [[char; 140]; 140] {
-    let mut input_grid = [['.'; COLS]; COLS];
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            input_grid[i][j] = c;
-        }
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input)
     }
-    input_grid
-}
 
-fn has_adjacent_symbol(grid: &[[char; COLS]; COLS], i: usize, j: usize) -> bool {
-    let min_i = if i > 0 { i - 1 } else { i };
-    let min_j = if j > 0 { j - 1 } else { j };
-    let max_i = (i + 1).min(COLS - 1);
-    let max_j = (j + 1).min(COLS - 1);
-    for i in min_i..=max_i {
-        for j in min_j..=max_j {
-            if grid[i][j] != '.' && grid[i][j].is_numeric() == false {
-                return true;
-            }
-        }
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(
+            black_box(Grid::parse(input, Some))
+                .map(drop)
+                .map_err(Error::from),
+        )
     }
-    false
 }
 
-fn get_index(i: usize, j: usize) -> usize {
-    (i * COLS) + j
-}
-
-fn part_two(input: &str, columns: usize) -> u64 {
-    let mut input_grid = ['.'; RANK];
-    for (i, line) in input.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
-            input_grid[get_index(i, j)] = c;
-        }
-    }
+fn part_one(input: &str) -> Result<u32, Error> {
+    let grid = Grid::parse(input, Some)?;
 
     let mut acc = 0;
-    let rows = columns;
-    for i in 0..rows {
-        for j in 0..columns {
-            let index = get_index(i, j);
-            if input_grid[index] == '*' {
-                acc += calc_adjacent_numbers(&input_grid, index);
+    for row in 0..grid.height() {
+        let mut col = 0;
+        while col < grid.width() {
+            if !grid[(row, col)].is_ascii_digit() {
+                col += 1;
+                continue;
+            }
+            let (_, number) = read_number(&grid, row, col)?;
+            let mut adjacent_symbol = false;
+            while col < grid.width() && grid[(row, col)].is_ascii_digit() {
+                adjacent_symbol |= has_adjacent_symbol(&grid, row, col);
+                col += 1;
+            }
+            if adjacent_symbol {
+                acc = checked_sum(acc, number)?;
             }
         }
     }
-
-    acc
+    Ok(acc)
 }
 
-fn calc_adjacent_numbers(grid: &[char; RANK], index: usize) -> u64 {
-    let index = index as i32;
-    let max_size = COLS as i32;
-    let positions = [
-        index - max_size - 1, // top left
-        index - max_size,     // above
-        index - max_size + 1, // top right
-        index - 1,            // left
-        index + 1,            // right,
-        index + max_size - 1, // bottom left
-        index + max_size,     // below
-        index + max_size + 1, // bottom right
-    ];
-    let mut numbers = [false; 8];
-
-    let max_index = (COLS * COLS) as i32;
-    for (j, &p) in positions.iter().enumerate() {
-        if p > 0 && p < max_index {
-            numbers[j] = grid[p as usize].is_numeric();
-        } else {
-            numbers[j] = false;
-        }
-    }
+fn has_adjacent_symbol(grid: &Grid<char>, row: usize, col: usize) -> bool {
+    grid.neighbours8((row, col)).any(|p| {
+        let c = grid[p];
+        c != '.' && !c.is_ascii_digit()
+    })
+}
 
-    let numbers = filter_adjacent_numbers(numbers);
-    if numbers.iter().filter(|&b| *b).count() != 2 {
-        return 0;
-    }
+fn part_two(input: &str) -> Result<u64, Error> {
+    let grid = Grid::parse(input, Some)?;
 
-    let mut acc = 1;
-    for i in 0..8 {
-        if numbers[i] {
-            acc *= read_number(positions[i] as usize, grid) as u64;
+    let mut acc = 0;
+    for (row, col) in grid.positions() {
+        if grid[(row, col)] == '*' {
+            acc = checked_sum(acc, calc_adjacent_numbers(&grid, row, col)?)?;
         }
     }
-
-    acc
+    Ok(acc)
 }
 
-fn filter_adjacent_numbers(numbers: [bool; 8]) -> [bool; 8] {
-    let mut output = numbers.clone();
-    if numbers[1] {
-        output[0] = false;
-        output[2] = false;
-    }
-    if numbers[6] {
-        output[5] = false;
-        output[7] = false;
+/// The gear ratio of the `*` at `(row, col)`, or 0 if it doesn't touch
+/// exactly two numbers.
+fn calc_adjacent_numbers(grid: &Grid<char>, row: usize, col: usize) -> Result<u64, Error> {
+    let mut numbers = grid
+        .neighbours8((row, col))
+        .filter(|&p| grid[p].is_ascii_digit())
+        .map(|(r, c)| read_number(grid, r, c))
+        .collect::<Result<Vec<((usize, usize), u32)>, Error>>()?;
+    // Several neighbours can be digits of the same number
+    numbers.sort();
+    numbers.dedup();
+
+    if numbers.len() != 2 {
+        return Ok(0);
     }
-    output
+    Ok(numbers.iter().map(|&(_, n)| n as u64).product())
 }
 
-fn read_number(i: usize, grid: &[char; COLS * COLS]) -> u32 {
-    let left_boundary = (i / COLS) * COLS; // inclusive
-    let right_boundary = ((i + 1) / COLS) * COLS;
-    let right_boundary = right_boundary.min(COLS * COLS); // exclusive
-
-    // Find last index
-    let mut index: i32 = i as i32;
-    while index > right_boundary as i32 {
-        if grid[index as usize + 1].is_numeric() {
-            index += 1;
-        } else {
-            break;
-        }
-    }
-
-    let mut acc = 0;
-    let mut num_consecutive = 0;
-    while index >= left_boundary as i32 && grid[index as usize].is_numeric() {
-        acc += grid[index as usize].to_digit(10).unwrap() * 10_u32.pow(num_consecutive);
-        num_consecutive += 1;
-        index -= 1;
-    }
-    acc
+/// Adds to a running total, which the answer has to fit in.
+fn checked_sum<T: TryFrom<u64> + Into<u64>>(acc: T, n: T) -> Result<T, Error> {
+    acc.into()
+        .checked_add(n.into())
+        .and_then(|sum| T::try_from(sum).ok())
+        .ok_or(Error::NoAnswer("the total is too large"))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Reads the whole number that the digit at `(row, col)` is part of,
+/// returning where it starts along with its value.
+fn read_number(grid: &Grid<char>, row: usize, col: usize) -> Result<((usize, usize), u32), Error> {
+    let digits = grid.row(row);
+    let start = digits[..col]
+        .iter()
+        .rposition(|c| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    let number = digits[start..]
+        .iter()
+        .map_while(|c| c.to_digit(10))
+        .try_fold(0u32, |acc, d| acc.checked_mul(10)?.checked_add(d));
+    let number = number.ok_or_else(|| ParseError {
+        line: row + 1,
+        column: start + 1,
+        kind: ErrorKind::InvalidInteger(
+            digits[start..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect(),
+        ),
+    })?;
+    Ok(((row, start), number))
+}
 
-    const INPUT: &str = "467..114..
+const INPUT: &str = "467..114..
 ...*......
 ..35..633.
 ......#...
 617*......
-....+.58.
+.....+.58.
 ..592.....
 ......755.
 ...$.*....
 .664.598..";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT, 10), 4361);
+        assert_eq!(part_one(INPUT), Ok(4361));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT, 10), 467835);
+        assert_eq!(part_two(INPUT), Ok(467835));
+    }
+
+    #[test]
+    fn test_read_number() {
+        let grid = Grid::parse("..123*\n45....", Some).unwrap();
+        assert_eq!(read_number(&grid, 0, 3), Ok(((0, 2), 123)));
+        assert_eq!(read_number(&grid, 1, 0), Ok(((1, 0), 45)));
+        assert_eq!(calc_adjacent_numbers(&grid, 0, 5), Ok(0));
+    }
+
+    #[test]
+    fn test_any_size() {
+        assert_eq!(part_one("1*\n.."), Ok(1));
+        assert_eq!(part_two("2*3"), Ok(6));
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        assert_eq!(part_one("4294967295*"), Ok(u32::MAX));
+        assert_eq!(part_two("4294967295*4294967295"), Ok(18446744065119617025));
+        assert_eq!(
+            part_one(".1111111111111*").unwrap_err().to_string(),
+            "line 1, column 2: invalid integer `1111111111111`"
+        );
+        assert!(part_two("1111111111111*2").is_err());
+        assert_eq!(
+            part_one("4294967295*\n4294967295*"),
+            Err(Error::NoAnswer("the total is too large"))
+        );
     }
 }
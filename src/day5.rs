@@ -1,116 +1,383 @@
-use sscanf::sscanf;
+use crate::error::Error;
+use crate::interval::IntervalSet;
+use crate::parsing::{blocks, Block, Line};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
+use std::ops::Range;
 
-pub fn main() {
-    let input = std::fs::read_to_string("input/day5").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
+pub struct Day5;
+impl Solution for Day5 {
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "35",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "46",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<u64, Error> {
+        part_one(input)
+    }
+
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input)
+    }
+
+    fn notes(&self, input: &str) -> Vec<String> {
+        let Ok(almanac) = Almanac::parse(input) else {
+            return vec![];
+        };
+        almanac
+            .gaps()
+            .into_iter()
+            .map(|(layer, gaps)| {
+                let ranges = gaps
+                    .iter()
+                    .map(|r| format!("{}..{}", r.start, r.end))
+                    .collect::<Vec<String>>();
+                format!(
+                    "{}-to-{} map keeps {} unchanged",
+                    layer.from,
+                    layer.to,
+                    ranges.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(Almanac::parse(input)).map(drop))
+    }
 }
 
-#[derive(Debug)]
+/// Sends `src..src + range` to `dst..dst + range`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct Map {
-    dst: u32,
-    src: u32,
-    range: u32,
+    dst: u64,
+    src: u64,
+    range: u64,
 }
 impl Map {
-    fn from_str(input: &str) -> Option<Self> {
-        if let Ok((dst, src, range)) = sscanf!(input, "{} {} {}", u32, u32, u32) {
-            return Some(Map { dst, src, range });
+    fn parse(line: Line) -> Result<Self, Error> {
+        let [dst, src, range]: [u64; 3] = line.array()?;
+        if dst.max(src).checked_add(range).is_none() {
+            return Err(Error::Invalid {
+                line: line.number,
+                reason: "map runs past the largest number",
+            });
         }
-        None
+        Ok(Map { dst, src, range })
+    }
+
+    fn src_end(&self) -> u64 {
+        self.src + self.range
+    }
+
+    fn dst_end(&self) -> u64 {
+        self.dst + self.range
     }
 }
 
-fn split_once<'a>(input: &'a str, pat: char) -> (&'a str, &'a str) {
-    let mid = input.find(pat).unwrap_or(input.len());
-    let (head, tail) = input.split_at(mid);
-    (&head[..mid], &tail[1..])
+/// A function on `0..u64::MAX` made of maps that cover it end to end, sorted
+/// by where they start.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct PiecewiseMap {
+    pieces: Vec<Map>,
 }
+impl PiecewiseMap {
+    /// Fills the gaps between sorted, non-overlapping maps with ones that
+    /// keep their numbers.
+    fn from_sorted(maps: &[Map]) -> Self {
+        let mut pieces = vec![];
+        let mut next = 0;
+        for map in maps.iter().filter(|m| m.range > 0) {
+            if map.src > next {
+                pieces.push(Map {
+                    dst: next,
+                    src: next,
+                    range: map.src - next,
+                });
+            }
+            pieces.push(*map);
+            next = map.src_end();
+        }
+        if next < u64::MAX {
+            pieces.push(Map {
+                dst: next,
+                src: next,
+                range: u64::MAX - next,
+            });
+        }
+        Self { pieces }
+    }
+
+    fn piece(&self, value: u64) -> &Map {
+        let i = self.pieces.partition_point(|p| p.src <= value);
+        &self.pieces[i - 1]
+    }
+
+    fn apply(&self, value: u64) -> u64 {
+        let piece = self.piece(value);
+        piece.dst + (value - piece.src)
+    }
+
+    /// Sends a whole range through, split wherever it crosses from one piece
+    /// to the next.
+    fn apply_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let mut output = vec![];
+        let mut start = range.start;
+        while start < range.end {
+            let piece = self.piece(start);
+            let end = range.end.min(piece.src_end());
+            output.push(piece.dst + (start - piece.src)..piece.dst + (end - piece.src));
+            start = end;
+        }
+        output
+    }
 
-fn parse_input(input: &str) -> (Vec<u32>, Vec<Vec<Map>>) {
-    let mut lines = input.lines();
-    let (_, tail) = split_once(lines.next().unwrap(), ':');
-    let seeds = tail
-        .split_whitespace()
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-
-    let mut output = vec![vec![]];
-    while let Some(l) = lines.next() {
-        if l.ends_with("map:") {
-            let mut maps = vec![];
-            while let Some(map) = lines.next().and_then(|l| Map::from_str(l)) {
-                maps.push(map);
+    /// The map that applies `self` and then `next`.
+    fn then(&self, next: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces = vec![];
+        for piece in self.pieces.iter() {
+            let mut src = piece.src;
+            for image in next.apply_range(piece.dst..piece.dst_end()) {
+                let range = image.end - image.start;
+                pieces.push(Map {
+                    dst: image.start,
+                    src,
+                    range,
+                });
+                src += range;
             }
-            output.push(maps);
         }
+        PiecewiseMap { pieces }
     }
-    (seeds, output)
-}
 
-fn part_one(input: &str) -> u64 {
-    let (seeds, maps) = parse_input(input);
-    let mut locations = vec![];
-    for s in seeds {
-        locations.push(propagate_seed(s, &maps));
+    /// The map that undoes this one, if every number comes from exactly one
+    /// other.
+    fn inverse(&self) -> Option<PiecewiseMap> {
+        let mut pieces = self
+            .pieces
+            .iter()
+            .map(|p| Map {
+                dst: p.src,
+                src: p.dst,
+                range: p.range,
+            })
+            .collect::<Vec<Map>>();
+        pieces.sort_by_key(|p| p.src);
+        let mut next = 0;
+        for piece in pieces.iter() {
+            if piece.src != next {
+                return None;
+            }
+            next = piece.src_end();
+        }
+        (next == u64::MAX).then_some(PiecewiseMap { pieces })
     }
-    locations.sort_by(|a, b| a.cmp(b));
-    locations[0]
 }
 
-fn propagate_seed(seed: u32, maps: &[Vec<Map>]) -> u64 {
-    let mut location = seed as u64;
-    'layers: for layer in maps.iter() {
-        for map in layer.iter() {
-            let lower_bound = map.src as u64;
-            let upper_bound = lower_bound + map.range as u64;
-            if location >= lower_bound && location < upper_bound {
-                let offset = location - lower_bound;
-                location = map.dst as u64 + offset;
-                continue 'layers;
+/// One `from-to-to map:` section.
+#[derive(Debug)]
+struct Layer<'a> {
+    from: &'a str,
+    to: &'a str,
+    /// Sorted by source, and never overlapping
+    maps: Vec<Map>,
+}
+impl<'a> Layer<'a> {
+    fn parse(block: &Block<'a>) -> Result<Self, Error> {
+        let invalid_label = Error::Invalid {
+            line: block.label.number,
+            reason: "expected a `<category>-to-<category> map:` label",
+        };
+        let (from, to) = block
+            .label
+            .text
+            .strip_suffix(" map")
+            .and_then(|l| l.split_once("-to-"))
+            .ok_or(invalid_label)?;
+
+        let mut maps = block
+            .lines
+            .iter()
+            .map(|&l| Ok((l.number, Map::parse(l)?)))
+            .collect::<Result<Vec<(usize, Map)>, Error>>()?;
+        maps.sort_by_key(|(_, m)| m.src);
+        for pair in maps.windows(2) {
+            if pair[1].1.src < pair[0].1.src_end() {
+                return Err(Error::Invalid {
+                    line: pair[0].0.max(pair[1].0),
+                    reason: "map overlaps another in the same section",
+                });
             }
         }
+        let maps = maps.into_iter().map(|(_, m)| m).collect();
+        Ok(Self { from, to, maps })
+    }
+
+    /// Ranges between the first and last maps that no map covers, where
+    /// numbers are kept as they are.
+    fn gaps(&self) -> IntervalSet<u64> {
+        let (Some(first), Some(last)) = (self.maps.first(), self.maps.last()) else {
+            return IntervalSet::default();
+        };
+        let span = IntervalSet::from(first.src..last.src_end());
+        span.difference(&self.maps.iter().map(|m| m.src..m.src_end()).collect())
+    }
+
+    fn piecewise(&self) -> PiecewiseMap {
+        PiecewiseMap::from_sorted(&self.maps)
     }
-    location
 }
 
-fn part_two(input: &str) -> u64 {
-    let (seeds, maps) = parse_input(input);
-    let mut seed_ranges = vec![];
-    for (i, start) in seeds.iter().enumerate().step_by(2) {
-        seed_ranges.push((*start, seeds[i + 1]));
-    }
-
-    for i in 0..u32::MAX {
-        let mut location = i as u64;
-        'layers: for layer in maps.iter().rev() {
-            for map in layer.iter().rev() {
-                let lower_bound = map.dst as u64;
-                let upper_bound = lower_bound + map.range as u64;
-                if location >= lower_bound && location < upper_bound {
-                    let offset = location - lower_bound;
-                    location = map.src as u64 + offset;
-                    continue 'layers;
-                }
+/// The seeds and the chain of sections leading from one category to the
+/// next.
+#[derive(Debug)]
+struct Almanac<'a> {
+    seeds: Vec<u64>,
+    layers: Vec<Layer<'a>>,
+}
+impl<'a> Almanac<'a> {
+    fn parse(input: &'a str) -> Result<Self, Error> {
+        let blocks = blocks(input)?;
+        let Some((seeds, layers)) = blocks.split_first() else {
+            return Err(Error::NoAnswer("the almanac is empty"));
+        };
+        if seeds.label.text != "seeds" {
+            return Err(Error::Invalid {
+                line: seeds.label.number,
+                reason: "expected the `seeds:` line first",
+            });
+        }
+        let seeds = seeds.header.integers()?;
+        let layers = layers
+            .iter()
+            .map(Layer::parse)
+            .collect::<Result<Vec<Layer>, Error>>()?;
+        for (i, pair) in layers.windows(2).enumerate() {
+            if pair[0].to != pair[1].from {
+                return Err(Error::Invalid {
+                    line: blocks[i + 2].label.number,
+                    reason: "section doesn't start from the category the last one ended on",
+                });
             }
         }
-        // Compare to seed ranges
-        for (start, range) in seed_ranges.iter() {
-            let start = *start as u64;
-            let end = start + *range as u64;
-            if location >= start as u64 && location < end {
-                return i as u64;
+        Ok(Self { seeds, layers })
+    }
+
+    /// Each section that leaves gaps between its maps, with those gaps.
+    fn gaps(&self) -> Vec<(&Layer<'a>, IntervalSet<u64>)> {
+        self.layers
+            .iter()
+            .map(|layer| (layer, layer.gaps()))
+            .filter(|(_, gaps)| !gaps.is_empty())
+            .collect()
+    }
+
+    /// All the sections one after the other.
+    fn compose(&self) -> PiecewiseMap {
+        self.layers
+            .iter()
+            .fold(PiecewiseMap::from_sorted(&[]), |acc, layer| {
+                acc.then(&layer.piecewise())
+            })
+    }
+}
+
+// Neither part asks about categories other than the first and last, so these
+// lookups are only exercised by the tests
+#[cfg_attr(not(test), allow(dead_code))]
+impl Almanac<'_> {
+    /// The map from one category to another, going back up the chain if
+    /// `to` comes first. `None` if either category is missing, or going back
+    /// would need a map that can't be undone.
+    fn map_between(&self, from: &str, to: &str) -> Option<PiecewiseMap> {
+        let identity = PiecewiseMap::from_sorted(&[]);
+        if from == to {
+            return Some(identity);
+        }
+        let start = self.layers.iter().position(|l| l.from == from);
+        let end = self.layers.iter().position(|l| l.to == to);
+        if let (Some(start), Some(end)) = (start, end) {
+            if start <= end {
+                return Some(
+                    self.layers[start..=end]
+                        .iter()
+                        .fold(identity, |acc, layer| acc.then(&layer.piecewise())),
+                );
             }
         }
+        let start = self.layers.iter().position(|l| l.to == from)?;
+        let end = self.layers.iter().position(|l| l.from == to)?;
+        if end > start {
+            return None;
+        }
+        self.layers[end..=start]
+            .iter()
+            .rev()
+            .try_fold(identity, |acc, layer| {
+                Some(acc.then(&layer.piecewise().inverse()?))
+            })
+    }
+
+    /// The value in category `to` of `value` in category `from`.
+    fn convert(&self, value: u64, from: &str, to: &str) -> Option<u64> {
+        Some(self.map_between(from, to)?.apply(value))
     }
-    0
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn part_one(input: &str) -> Result<u64, Error> {
+    let almanac = Almanac::parse(input)?;
+    let map = almanac.compose();
+    almanac
+        .seeds
+        .iter()
+        .map(|&s| map.apply(s))
+        .min()
+        .ok_or(Error::NoAnswer("no seeds to plant"))
+}
 
-    const INPUT: &str = "seeds: 79 14 55 13
+/// The seeds come as ranges, which are sent through the whole almanac at
+/// once and only split where it maps their parts differently.
+fn part_two(input: &str) -> Result<u64, Error> {
+    let almanac = Almanac::parse(input)?;
+    if almanac.seeds.len() % 2 != 0 {
+        return Err(Error::Invalid {
+            line: 1,
+            reason: "seeds must come in start and length pairs",
+        });
+    }
+    let seeds = almanac
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| {
+            let end = pair[0].checked_add(pair[1]).ok_or(Error::Invalid {
+                line: 1,
+                reason: "seed range runs past the largest number",
+            })?;
+            Ok(pair[0]..end)
+        })
+        .collect::<Result<IntervalSet<u64>, Error>>()?;
+    let map = almanac.compose();
+    let locations = seeds
+        .iter()
+        .flat_map(|r| map.apply_range(r))
+        .collect::<IntervalSet<u64>>();
+    locations.min().ok_or(Error::NoAnswer("no seeds to plant"))
+}
+
+const INPUT: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
@@ -144,13 +411,138 @@ humidity-to-location map:
 60 56 37
 56 93 4";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT), 35);
+        assert_eq!(part_one(INPUT), Ok(35));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT), 46);
+        assert_eq!(part_two(INPUT), Ok(46));
+    }
+
+    #[test]
+    fn test_piecewise_map() {
+        let maps = [
+            Map {
+                dst: 100,
+                src: 10,
+                range: 5,
+            },
+            Map {
+                dst: 0,
+                src: 15,
+                range: 5,
+            },
+        ];
+        let map = PiecewiseMap::from_sorted(&maps);
+        assert_eq!(map.apply(9), 9);
+        assert_eq!(map.apply(12), 102);
+        assert_eq!(map.apply(19), 4);
+        assert_eq!(map.apply(u64::MAX - 1), u64::MAX - 1);
+        let mut ranges = map.apply_range(5..17);
+        ranges.sort_by_key(|r| r.start);
+        assert_eq!(ranges, vec![0..2, 5..10, 100..105]);
+
+        // Numbers 0..5 are sent to 0..5 and to 15..20, so nothing undoes it
+        assert_eq!(map.inverse(), None);
+        let swap = PiecewiseMap::from_sorted(&[
+            Map {
+                dst: 5,
+                src: 0,
+                range: 5,
+            },
+            Map {
+                dst: 0,
+                src: 5,
+                range: 5,
+            },
+        ]);
+        let inverse = swap.inverse().unwrap();
+        assert_eq!(inverse, swap);
+        for value in [0, 3, 7, 12] {
+            assert_eq!(inverse.apply(swap.apply(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_almanac() {
+        let almanac = Almanac::parse(INPUT).unwrap();
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+        assert_eq!(almanac.layers[0].from, "seed");
+        assert_eq!(almanac.layers[6].to, "location");
+        assert_eq!(almanac.convert(79, "seed", "soil"), Some(81));
+        assert_eq!(almanac.convert(79, "seed", "location"), Some(82));
+        assert_eq!(almanac.convert(81, "soil", "water"), Some(81));
+        assert_eq!(almanac.convert(82, "location", "seed"), Some(79));
+        assert_eq!(almanac.convert(46, "location", "seed"), Some(82));
+        assert_eq!(almanac.convert(7, "seed", "seed"), Some(7));
+        assert_eq!(almanac.convert(7, "seed", "sand"), None);
+
+        let map = almanac.compose();
+        assert_eq!(map.apply(14), 43);
+        assert_eq!(map.inverse().map(|inv| inv.apply(43)), Some(14));
+        assert!(almanac.gaps().is_empty());
+        assert!(Day5.notes(INPUT).is_empty());
+    }
+
+    #[test]
+    fn test_almanac_errors() {
+        let overlap = "seeds: 1\n\na-to-b map:\n0 10 5\n0 12 5";
+        assert_eq!(
+            Almanac::parse(overlap).unwrap_err().to_string(),
+            "line 5: map overlaps another in the same section"
+        );
+        let gapped = "seeds: 1\n\na-to-b map:\n0 10 5\n0 20 5\n\nb-to-c map:\n0 0 5";
+        let almanac = Almanac::parse(gapped).unwrap();
+        let gaps = almanac.gaps();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].0.from, "a");
+        assert_eq!(gaps[0].1.iter().collect::<Vec<_>>(), vec![15..20]);
+        assert_eq!(
+            Day5.notes(gapped),
+            vec!["a-to-b map keeps 15..20 unchanged".to_string()]
+        );
+        let broken = "seeds: 1\n\na-to-b map:\n0 10 5\n\nc-to-d map:\n0 20 5";
+        assert_eq!(
+            Almanac::parse(broken).unwrap_err().to_string(),
+            "line 6: section doesn't start from the category the last one ended on"
+        );
+        assert!(Almanac::parse("seeds: 1\n\nsoil map:\n0 1 2").is_err());
+        assert!(Almanac::parse("plants: 1").is_err());
+        let too_far = "seeds: 1\n\na-to-b map:\n0 18446744073709551610 10";
+        assert_eq!(
+            Almanac::parse(too_far).unwrap_err().to_string(),
+            "line 4: map runs past the largest number"
+        );
+        let to_the_end = "seeds: 1\n\na-to-b map:\n18446744073709551610 0 5";
+        assert!(Almanac::parse(to_the_end).is_ok());
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let input = "seeds: 5000000000 10
+
+a-to-b map:
+7000000000 5000000005 100";
+        assert_eq!(part_one(input), Ok(10));
+        assert_eq!(part_two(input), Ok(5000000000));
+        let input = input.replace("7000000000", "7");
+        assert_eq!(part_two(&input), Ok(7));
+        assert_eq!(
+            part_two("seeds: 18446744073709551610 10"),
+            Err(Error::Invalid {
+                line: 1,
+                reason: "seed range runs past the largest number"
+            })
+        );
+        assert_eq!(
+            part_two("seeds: 18446744073709551610 5"),
+            Ok(18446744073709551610)
+        );
     }
 }
@@ -1,96 +1,160 @@
-use crate::parsing::*;
+use crate::error::Error;
+use crate::math;
+use crate::parsing::{headers, ErrorKind, Line, ParseError};
+use crate::solution::{Example, Part, Solution};
 
-pub fn main() {
-    let input = std::fs::read_to_string("input/day6").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
-}
+pub struct Day6;
+impl Solution for Day6 {
+    type PartOne = u64;
+    type PartTwo = u64;
 
-fn part_one(input: &str) -> u64 {
-    let mut times = vec![];
-    let mut distances = vec![];
-    for l in input.lines() {
-        let (head, tail) = split_once(l, ':');
-        match head {
-            "Time" => {
-                times = tail
-                    .split_whitespace()
-                    .map(|s| s.parse::<i32>().unwrap())
-                    .collect()
-            }
-            "Distance" => {
-                distances = tail
-                    .split_whitespace()
-                    .map(|s| s.parse::<i32>().unwrap())
-                    .collect()
-            }
-            _ => {}
-        }
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Wait For It";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "288",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "71503",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<u64, Error> {
+        part_one(input)
     }
 
-    let mut acc = 1_u64;
-    for i in 0..times.len() {
-        let (min, max) = get_int_bounds(distances[i] as f64, times[i] as f64);
-        acc *= (max as i32 - min as i32) as u64;
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input)
     }
-    acc
 }
 
-fn get_int_bounds(distance: f64, time: f64) -> (u64, u64) {
-    // Quadratic solution to x(T-x) = d where T is total time, d is min distance
-    let half_time = time / 2.0;
-    let min = (half_time) - ((half_time).powf(2.0) - distance).sqrt();
-    let max = (half_time) + ((half_time).powf(2.0) - distance).sqrt();
-
-    let mut min = min as u64;
-    let min_distance = min * (time as u64 - min);
-    if min_distance == distance as u64 {
-        min += 1;
+fn part_one(input: &str) -> Result<u64, Error> {
+    let headers = headers(input)?;
+    let times = headers.get("Time")?.integers::<u64>()?;
+    let distances = headers.get("Distance")?;
+    let line = distances.number;
+    let distances = distances.integers::<u64>()?;
+    if distances.len() != times.len() {
+        return Err(Error::Invalid {
+            line,
+            reason: "every race needs both a time and a distance",
+        });
     }
-    (min, max as u64)
+
+    Ok(times
+        .iter()
+        .zip(distances.iter())
+        .map(|(&time, &record)| winning_holds(time, record).count)
+        .product())
 }
 
-fn part_two(input: &str) -> u64 {
-    let mut time = 0;
-    let mut distance = 0;
-    for l in input.lines() {
-        let (head, tail) = split_once(l, ':');
-        match head {
-            "Time" => {
-                time = tail
-                    .split_whitespace()
-                    .collect::<String>()
-                    .parse::<u64>()
-                    .unwrap();
-            }
-            "Distance" => {
-                distance = tail
-                    .split_whitespace()
-                    .collect::<String>()
-                    .parse::<u64>()
-                    .unwrap();
-            }
-            _ => {}
-        }
+/// How a race can be won.
+#[derive(Debug, PartialEq, Eq)]
+struct Wins {
+    /// How many whole millisecond holds beat the record
+    count: u64,
+    /// The hold that goes furthest
+    best_hold: u64,
+}
+
+/// Holding for `h` of the `time` milliseconds goes `h * (time - h)`, so the
+/// winning holds lie between the roots of `h * (time - h) = record`. The
+/// roots are found with an integer square root and nudged onto the first
+/// hold that wins, so no precision is lost however big the race is.
+fn winning_holds(time: u64, record: u64) -> Wins {
+    let (time, record) = (time as u128, record as u128);
+    let distance = |hold: u128| hold * (time - hold);
+    let best_hold = time / 2;
+    if distance(best_hold) <= record {
+        return Wins {
+            count: 0,
+            best_hold: best_hold as u64,
+        };
+    }
+
+    let root = math::isqrt(time * time - 4 * record);
+    let mut low = (time - root) / 2;
+    while distance(low) <= record {
+        low += 1;
+    }
+    while low > 0 && distance(low - 1) > record {
+        low -= 1;
+    }
+    // The distances are symmetric about `time / 2`
+    let high = time - low;
+    Wins {
+        count: (high - low + 1) as u64,
+        best_hold: best_hold as u64,
     }
-    let (min, max) = get_int_bounds(distance as f64, time as f64);
-    return (max - min) as u64;
 }
 
+fn part_two(input: &str) -> Result<u64, Error> {
+    let headers = headers(input)?;
+    let time = concatenated(headers.get("Time")?)?;
+    let distance = concatenated(headers.get("Distance")?)?;
+    Ok(winning_holds(time, distance).count)
+}
+
+/// Reads the digits of a header as one number, ignoring the spaces between
+/// them.
+fn concatenated(value: Line) -> Result<u64, ParseError> {
+    let digits = value.fields().map(|f| f.text).collect::<String>();
+    digits
+        .parse()
+        .map_err(|_| value.error(ErrorKind::InvalidInteger(digits)))
+}
+
+const INPUT: &str = "Time:      7  15   30
+Distance:  9  40  200";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "Time:      7  15   30
-Distance:  9  40  200";
-
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT), 288);
+        assert_eq!(part_one(INPUT), Ok(288));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT), 71503);
+        assert_eq!(part_two(INPUT), Ok(71503));
+    }
+
+    #[test]
+    fn test_winning_holds() {
+        assert_eq!(
+            winning_holds(7, 9),
+            Wins {
+                count: 4,
+                best_hold: 3
+            }
+        );
+        // Holding for 10 or 20 only ties the record
+        assert_eq!(
+            winning_holds(30, 200),
+            Wins {
+                count: 9,
+                best_hold: 15
+            }
+        );
+        assert_eq!(winning_holds(4, 4).count, 0);
+        assert_eq!(winning_holds(4, 3).count, 1);
+        assert_eq!(winning_holds(0, 0).count, 0);
+        // Only holds within 4 of the middle beat 4e18 - 25, which is far
+        // beyond what an `f64` can tell apart
+        assert_eq!(
+            winning_holds(4_000_000_000, 4_000_000_000_000_000_000 - 25),
+            Wins {
+                count: 9,
+                best_hold: 2_000_000_000
+            }
+        );
+        // Holding for 1 or `u64::MAX - 1` falls just short of the record
+        assert_eq!(winning_holds(u64::MAX, u64::MAX).count, u64::MAX - 3);
     }
 }
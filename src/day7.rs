@@ -1,17 +1,45 @@
 use std::cmp::Ordering;
 
-use crate::parsing::*;
+use crate::error::Error;
+use crate::parsing::{lines, ErrorKind, Line};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
 
-pub fn main() {
-    let input = std::fs::read_to_string("input/day7").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
+pub struct Day7;
+impl Solution for Day7 {
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Camel Cards";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "6440",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "5905",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<u64, Error> {
+        part_one(input)
+    }
+
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input)
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(lines(input).try_for_each(|l| black_box(parse_line(l)).map(drop)))
+    }
 }
 
-#[allow(dead_code)] // False positive
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 enum CardLabel {
-    Joker,
     Two,
     Three,
     Four,
@@ -26,10 +54,51 @@ enum CardLabel {
     King,
     Ace,
 }
+impl CardLabel {
+    /// Every label, from lowest to highest.
+    const ALL: [CardLabel; 13] = [
+        CardLabel::Two,
+        CardLabel::Three,
+        CardLabel::Four,
+        CardLabel::Five,
+        CardLabel::Six,
+        CardLabel::Seven,
+        CardLabel::Eight,
+        CardLabel::Nine,
+        CardLabel::Ten,
+        CardLabel::Jack,
+        CardLabel::Queen,
+        CardLabel::King,
+        CardLabel::Ace,
+    ];
+}
+
+/// Reads a card as printed on it, `2` to `9` or one of `TJQKA`.
+impl TryFrom<char> for CardLabel {
+    type Error = char;
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '2'..='9' => Ok(CardLabel::ALL[c as usize - '2' as usize]),
+            'T' => Ok(CardLabel::Ten),
+            'J' => Ok(CardLabel::Jack),
+            'Q' => Ok(CardLabel::Queen),
+            'K' => Ok(CardLabel::King),
+            'A' => Ok(CardLabel::Ace),
+            _ => Err(c),
+        }
+    }
+}
+
+/// The label at a position in `CardLabel::ALL`.
+impl TryFrom<u8> for CardLabel {
+    type Error = u8;
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        CardLabel::ALL.get(index as usize).copied().ok_or(index)
+    }
+}
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 enum HandType {
-    None,
     HighCard,
     OnePair,
     TwoPair,
@@ -39,208 +108,224 @@ enum HandType {
     FiveOfAKind,
 }
 
-const NUM_CARDS: usize = 5;
-type Cards = [CardLabel; NUM_CARDS];
-
+/// How hands are scored: the order cards rank in when hands of the same type
+/// are compared, and which card, if any, stands in for whatever makes the
+/// best hand.
 #[derive(Debug)]
-struct Hand {
-    cards: Cards,
-    bid: i32,
-    ty: HandType,
+struct Ruleset {
+    /// From lowest to highest
+    order: [CardLabel; 13],
+    wild: Option<CardLabel>,
 }
+impl Ruleset {
+    const STANDARD: Ruleset = Ruleset {
+        order: CardLabel::ALL,
+        wild: None,
+    };
 
-fn parse_line(line: &str) -> Option<(Cards, i32)> {
-    let (cards, bid) = split_once(line, ' ');
-    let bid = bid.parse::<i32>().ok()?;
-    let mut card_hand = [CardLabel::Two; NUM_CARDS];
-    for (i, c) in cards.chars().enumerate() {
-        if let Some(digit) = c.to_digit(10) {
-            assert!(digit > 1 && digit < 10);
-            unsafe {
-                card_hand[i] = std::mem::transmute(digit as u8 - 1);
-            }
-        } else {
-            let label = match c {
-                'T' => CardLabel::Ten,
-                'J' => CardLabel::Jack,
-                'Q' => CardLabel::Queen,
-                'K' => CardLabel::King,
-                'A' => CardLabel::Ace,
-                _ => return None,
-            };
-            card_hand[i] = label;
+    /// Jacks become jokers, which are wild but rank below every other card.
+    const JOKERS: Ruleset = {
+        use CardLabel::*;
+        Ruleset {
+            order: [
+                Jack, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Queen, King, Ace,
+            ],
+            wild: Some(Jack),
         }
-    }
-    Some((card_hand, bid))
-}
+    };
 
-fn get_hand_type(cards: Cards) -> HandType {
-    let mut num_types = 1;
-    let mut sorted_cards = cards.clone();
-    sorted_cards.sort();
-    for i in 1..cards.len() {
-        if sorted_cards[i - 1] != sorted_cards[i] {
-            num_types += 1;
-        }
+    fn rank(&self, card: CardLabel) -> usize {
+        self.order.iter().position(|&c| c == card).unwrap_or(0)
     }
 
-    match num_types {
-        1 => HandType::FiveOfAKind,
-        2 => {
-            let num_of_one_type = cards.iter().filter(|&c| c == &cards[0]).count();
-            if num_of_one_type == 1 || num_of_one_type == 4 {
-                HandType::FourOfAKind
+    /// Classifies a hand from how many of each card it has. A wild card is
+    /// always best used to grow the biggest group.
+    fn hand_type(&self, cards: &Cards) -> HandType {
+        let mut counts = [0; 13];
+        let mut wild = 0;
+        for &card in cards {
+            if Some(card) == self.wild {
+                wild += 1;
             } else {
-                HandType::FullHouse
+                counts[card as usize] += 1;
             }
         }
-        3 => {
-            let num_of_first_type = sorted_cards
-                .iter()
-                .filter(|&c| c == &sorted_cards[0])
-                .count();
-            let num_of_second_type = sorted_cards
-                .iter()
-                .filter(|&c| c == &sorted_cards[2])
-                .count();
-            let num_of_third_type = sorted_cards
-                .iter()
-                .filter(|&c| c == &sorted_cards[4])
-                .count();
-            if num_of_first_type
-                .max(num_of_second_type)
-                .max(num_of_third_type)
-                == 3
-            {
-                HandType::ThreeOfAKind
-            } else {
-                HandType::TwoPair
-            }
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match (counts[0] + wild, counts[1]) {
+            (5, _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
         }
-        4 => HandType::OnePair,
-        5 => HandType::HighCard,
-        _ => HandType::None,
     }
-}
 
-fn part_one(input: &str) -> u64 {
-    let mut data = Vec::with_capacity(input.lines().count() - 1);
-    for l in input.lines() {
-        if let Some((cards, bid)) = parse_line(l) {
-            data.push(Hand {
-                cards,
-                bid,
-                ty: get_hand_type(cards),
-            })
-        }
+    fn compare(&self, a: &Hand, b: &Hand) -> Ordering {
+        a.ty.cmp(&b.ty).then_with(|| {
+            let ranks = |hand: &Hand| hand.cards.map(|c| self.rank(c));
+            ranks(a).cmp(&ranks(b))
+        })
     }
+}
 
-    sort_hands_by_rank(&mut data);
-    calculate_score(data)
+const NUM_CARDS: usize = 5;
+type Cards = [CardLabel; NUM_CARDS];
+
+#[derive(Debug)]
+struct Hand {
+    cards: Cards,
+    bid: u64,
+    ty: HandType,
 }
 
-fn part_two(input: &str) -> u64 {
-    let mut hands = Vec::with_capacity(input.lines().count() - 1);
-    for l in input.lines() {
-        if let Some((mut cards, bid)) = parse_line(l) {
-            for i in 0..cards.len() {
-                if cards[i] == CardLabel::Jack {
-                    cards[i] = CardLabel::Joker;
-                }
-            }
-            hands.push(Hand {
-                cards,
-                bid,
-                ty: HandType::None,
-            });
-        }
+fn parse_line(line: Line) -> Result<(Cards, u64), Error> {
+    let (cards, bid) = line.split_once(' ')?;
+    let bid = bid.integer::<u64>()?;
+    if cards.text.chars().count() != NUM_CARDS {
+        return Err(Error::Invalid {
+            line: line.number,
+            reason: "a hand has exactly five cards",
+        });
+    }
+    let mut card_hand = [CardLabel::Two; NUM_CARDS];
+    for (i, c) in cards.text.char_indices() {
+        card_hand[i] =
+            CardLabel::try_from(c).map_err(|c| cards.error_at(i, ErrorKind::UnexpectedChar(c)))?;
     }
+    Ok((card_hand, bid))
+}
 
-    // Get the highest type for each card
-    for i in 0..hands.len() {
-        if hands[i].cards.contains(&CardLabel::Joker) {
-            unsafe {
-                let max_index: u8 = std::mem::transmute(CardLabel::Ace);
-                for j in 0..=max_index {
-                    let joker_replacement: CardLabel = std::mem::transmute(j);
-                    let mut hand = [CardLabel::Two; NUM_CARDS];
-                    for k in 0..NUM_CARDS {
-                        if hands[i].cards[k] == CardLabel::Joker {
-                            hand[k] = joker_replacement;
-                        } else {
-                            hand[k] = hands[i].cards[k].into();
-                        }
-                    }
-
-                    let ty = get_hand_type(hand);
-                    hands[i].ty = hands[i].ty.max(ty);
-                }
-            }
-        } else {
-            // No joker present
-            hands[i].ty = get_hand_type(hands[i].cards);
-        }
+/// Ranks every hand under `rules` and adds up each bid times its rank.
+fn total_winnings(input: &str, rules: &Ruleset) -> Result<u64, Error> {
+    let mut hands = Vec::with_capacity(input.lines().count());
+    for l in lines(input) {
+        let (cards, bid) = parse_line(l)?;
+        hands.push(Hand {
+            cards,
+            bid,
+            ty: rules.hand_type(&cards),
+        })
     }
 
-    sort_hands_by_rank(&mut hands);
-    calculate_score(hands)
+    hands.sort_by(|a, b| rules.compare(a, b));
+    calculate_score(hands).ok_or(Error::NoAnswer("the total winnings don't fit in 64 bits"))
 }
 
-fn calculate_score(hands: Vec<Hand>) -> u64 {
-    let mut acc: u64 = 0;
-    for i in 0..hands.len() {
-        let score = hands[i].bid as u64 * (i + 1) as u64;
-        acc += score
-    }
-    acc
+fn part_one(input: &str) -> Result<u64, Error> {
+    total_winnings(input, &Ruleset::STANDARD)
 }
 
-fn sort_hands_by_rank(hands: &mut Vec<Hand>) {
-    hands.sort_by(|d1, d2| match d1.ty.cmp(&d2.ty) {
-        Ordering::Equal => {
-            for i in 0..d1.cards.len() {
-                match d1.cards[i].cmp(&d2.cards[i]) {
-                    Ordering::Equal => continue,
-                    o => return o,
-                }
-            }
-            Ordering::Equal
-        }
-        o => o,
-    });
+fn part_two(input: &str) -> Result<u64, Error> {
+    total_winnings(input, &Ruleset::JOKERS)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const INPUT: &str = "32T3K 765
+/// The sum of each bid times its rank, or `None` if it doesn't fit.
+fn calculate_score(hands: Vec<Hand>) -> Option<u64> {
+    let mut acc: u64 = 0;
+    for (i, hand) in hands.iter().enumerate() {
+        let score = hand.bid.checked_mul(i as u64 + 1)?;
+        acc = acc.checked_add(score)?;
+    }
+    Some(acc)
+}
+
+const INPUT: &str = "32T3K 765
 T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_parse_line() {
         use CardLabel::*;
         assert_eq!(
-            parse_line(INPUT.lines().nth(0).unwrap()),
-            Some(([Three, Two, Ten, Three, King], 765))
-        )
+            parse_line(Line::new(1, "32T3K 765")),
+            Ok(([Three, Two, Ten, Three, King], 765))
+        );
+        assert_eq!(
+            parse_line(Line::new(3, "32T3 765")),
+            Err(Error::Invalid {
+                line: 3,
+                reason: "a hand has exactly five cards"
+            })
+        );
+        assert!(parse_line(Line::new(1, "32T3X 765")).is_err());
+    }
+
+    #[test]
+    fn test_card_label_conversions() {
+        assert_eq!(CardLabel::try_from('2'), Ok(CardLabel::Two));
+        assert_eq!(CardLabel::try_from('9'), Ok(CardLabel::Nine));
+        assert_eq!(CardLabel::try_from('A'), Ok(CardLabel::Ace));
+        assert_eq!(CardLabel::try_from('1'), Err('1'));
+        assert_eq!(CardLabel::try_from('j'), Err('j'));
+        assert_eq!(CardLabel::try_from(0u8), Ok(CardLabel::Two));
+        assert_eq!(CardLabel::try_from(12u8), Ok(CardLabel::Ace));
+        assert_eq!(CardLabel::try_from(13u8), Err(13));
+        for (i, card) in CardLabel::ALL.into_iter().enumerate() {
+            assert_eq!(CardLabel::try_from(i as u8), Ok(card));
+        }
     }
 
     #[test]
     fn test_get_hand_type() {
-        let (card_hand, _) = parse_line(INPUT.lines().nth(0).unwrap()).unwrap();
-        assert_eq!(get_hand_type(card_hand), HandType::OnePair);
+        let hand_type = |rules: &Ruleset, text| {
+            let (cards, _) = parse_line(Line::new(1, text)).unwrap();
+            rules.hand_type(&cards)
+        };
+        let standard = &Ruleset::STANDARD;
+        assert_eq!(hand_type(standard, "32T3K 765"), HandType::OnePair);
+        assert_eq!(hand_type(standard, "KK677 28"), HandType::TwoPair);
+        assert_eq!(hand_type(standard, "T55J5 684"), HandType::ThreeOfAKind);
+        assert_eq!(hand_type(standard, "23332 1"), HandType::FullHouse);
+        assert_eq!(hand_type(standard, "AA8AA 1"), HandType::FourOfAKind);
+        assert_eq!(hand_type(standard, "23456 1"), HandType::HighCard);
+
+        let jokers = &Ruleset::JOKERS;
+        assert_eq!(hand_type(jokers, "T55J5 684"), HandType::FourOfAKind);
+        assert_eq!(hand_type(jokers, "KTJJT 220"), HandType::FourOfAKind);
+        assert_eq!(hand_type(jokers, "2J233 1"), HandType::FullHouse);
+        assert_eq!(hand_type(jokers, "2345J 1"), HandType::OnePair);
+        assert_eq!(hand_type(jokers, "JJJJJ 1"), HandType::FiveOfAKind);
+        assert_eq!(hand_type(jokers, "JJJJ2 1"), HandType::FiveOfAKind);
+    }
+
+    #[test]
+    fn test_custom_ruleset() {
+        // Twos are wild but otherwise rank normally
+        let rules = Ruleset {
+            order: CardLabel::ALL,
+            wild: Some(CardLabel::Two),
+        };
+        let input = "22AAK 1\nQQQ3K 10";
+        // Three queens beat two pairs, until the twos make four aces
+        assert_eq!(total_winnings(input, &Ruleset::STANDARD), Ok(21));
+        assert_eq!(total_winnings(input, &Ruleset::JOKERS), Ok(21));
+        assert_eq!(total_winnings(input, &rules), Ok(12));
     }
 
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT), 6440);
+        assert_eq!(part_one(INPUT), Ok(6440));
     }
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT), 5905);
+        assert_eq!(part_two(INPUT), Ok(5905));
+    }
+
+    #[test]
+    fn test_large_bids() {
+        let max = u64::MAX;
+        let too_large = Err(Error::NoAnswer("the total winnings don't fit in 64 bits"));
+        assert_eq!(part_one(&format!("23456 {max}\n34567 {max}")), too_large);
+        assert_eq!(part_one(&format!("23456 1\n34567 {}", max / 2)), Ok(max));
+        assert_eq!(part_one(&format!("23456 {max}")), Ok(max));
     }
 }
@@ -1,156 +1,189 @@
-use core::fmt::Debug;
-use sscanf::sscanf;
-use std::{fmt::Display, ptr};
-
-pub fn main() {
-    let input = std::fs::read_to_string("input/day8").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
-}
+use crate::cycle::{self, Orbit};
+use crate::error::Error;
+use crate::graph::{Graph, NodeId};
+use crate::parsing::{lines, ErrorKind, Line};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
 
-#[derive(Debug)]
-struct Node {
-    name: [u8; 3],
-    left: *const Node,
-    right: *const Node,
-}
-impl Display for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let text = std::str::from_utf8(&self.name).unwrap();
-        write!(f, "{}", text)
+pub struct Day8;
+impl Solution for Day8 {
+    type PartOne = usize;
+    type PartTwo = u64;
+
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT1,
+            answer: "2",
+        },
+        Example {
+            part: Part::One,
+            input: INPUT2,
+            answer: "6",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT3,
+            answer: "6",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<usize, Error> {
+        part_one(input)
     }
-}
 
-fn part_one(input: &str) -> usize {
-    let instructions = input.lines().nth(0).unwrap().as_bytes();
-    let map = build_map(input);
-
-    // Follow the instructions
-    let mut num_steps = 0;
-    let start_pos = map.iter().position(|n| n.name == [b'A'; 3]).unwrap();
-    let mut position = ptr::addr_of!(map[start_pos]);
-    loop {
-        unsafe {
-            let index = num_steps % instructions.len();
-            let this_node: Node = ptr::read(position);
-            if this_node.name == [b'Z'; 3] {
-                break;
-            }
-            num_steps += 1;
-            match instructions[index] {
-                b'L' => position = this_node.left,
-                b'R' => position = this_node.right,
-                _ => panic!("faulty instruction"),
-            }
-        }
+    fn part_two(&self, input: &str) -> Result<u64, Error> {
+        part_two(input)
     }
 
-    num_steps
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(build_map(input)).map(drop))
+    }
 }
 
-fn build_map(input: &str) -> Vec<Node> {
-    // Fill map with locations
-    let lines = input.lines();
-    let mut map = Vec::with_capacity(lines.clone().count());
-    for l in lines.clone() {
-        if let Ok((name, _, _)) = sscanf!(l, "{} = ({}, {})", &str, &str, &str) {
-            map.push(Node {
-                name: name.get(0..3).unwrap().as_bytes().try_into().unwrap(),
-                left: ptr::null(),
-                right: ptr::null(),
-            })
-        }
-    }
+/// The desert map, where each node's first edge leads left and its second
+/// leads right.
+type Network = Graph<[u8; 3]>;
 
-    // Fill map with addresses
-    let mut i = 0;
-    for l in lines.clone() {
-        if let Ok((_, left, right)) = sscanf!(l, "{} = ({}, {})", &str, &str, &str) {
-            let left: [u8; 3] = left.get(0..3).unwrap().as_bytes().try_into().unwrap();
-            let right: [u8; 3] = right.get(0..3).unwrap().as_bytes().try_into().unwrap();
+/// The node reached by taking the `L` or `R` turn from `node`.
+fn turn(map: &Network, node: NodeId, instruction: u8) -> NodeId {
+    map.neighbours(node)[usize::from(instruction == b'R')]
+}
 
-            let p_left = map.iter().position(|n| n.name == left).unwrap();
-            let p_right = map.iter().position(|n| n.name == right).unwrap();
-            map[i].left = ptr::addr_of!(map[p_left]);
-            map[i].right = ptr::addr_of!(map[p_right]);
+fn part_one(input: &str) -> Result<usize, Error> {
+    let instructions = parse_instructions(input)?;
+    let map = build_map(input)?;
 
-            i += 1;
+    let mut position = map
+        .id(b"AAA")
+        .ok_or(Error::NoAnswer("no `AAA` node to start from"))?;
+    let end = map
+        .id(b"ZZZ")
+        .ok_or(Error::NoAnswer("no `ZZZ` node to reach"))?;
+
+    // Follow the instructions. There are only so many pairs of a node and a
+    // place in the instructions, so after that many steps it's going round
+    // in circles
+    for num_steps in 0..map.len() * instructions.len() {
+        if position == end {
+            return Ok(num_steps);
         }
+        position = turn(&map, position, instructions[num_steps % instructions.len()]);
     }
-    map
+    Err(Error::NoAnswer("`ZZZ` can't be reached from `AAA`"))
 }
 
-fn part_two(input: &str) -> u64 {
-    let instructions = input.lines().nth(0).unwrap().as_bytes();
-    let map = build_map(input);
-
-    let mut positions = vec![];
-    for node in map.iter() {
-        if node.name[2] == b'A' {
-            positions.push(ptr::addr_of!(*node));
-        }
+/// Reads the `L`/`R` instructions on the first line.
+fn parse_instructions(input: &str) -> Result<&[u8], Error> {
+    let line = lines(input).next().unwrap_or(Line::new(1, ""));
+    if let Some(i) = line.text.find(|c| c != 'L' && c != 'R') {
+        let c = line.text[i..].chars().next().unwrap_or_default();
+        return Err(line.error_at(i, ErrorKind::UnexpectedChar(c)).into());
     }
+    if line.text.is_empty() {
+        return Err(Error::Invalid {
+            line: 1,
+            reason: "expected `L` and `R` instructions",
+        });
+    }
+    Ok(line.text.as_bytes())
+}
 
-    let mut num_steps = 0;
-    let mut num_steps_to_finish = vec![];
-    loop {
-        let instruction_index = num_steps % instructions.len();
-
-        unsafe {
-            let mut j = 0;
-            while j < positions.len() {
-                if ptr::read(positions[j]).name[2] == b'Z' {
-                    positions.swap_remove(j);
-                    num_steps_to_finish.push(num_steps);
-                } else {
-                    j += 1;
-                }
-            }
+/// Reads `AAA = (BBB, CCC)` into the node name and its left and right
+/// neighbours.
+fn parse_node(line: Line) -> Result<[Line; 3], Error> {
+    let (name, edges) = line.split_once('=')?;
+    let edges = edges.trim();
+    let (left, right) = edges.split_once(',')?;
+    let left = left.split_once('(')?.1.trim();
+    let right = right.split_once(')')?.0.trim();
+    let nodes = [name.trim(), left, right];
+    if nodes.iter().any(|n| n.text.len() != 3) {
+        return Err(Error::Invalid {
+            line: line.number,
+            reason: "node names are three characters long",
+        });
+    }
+    Ok(nodes)
+}
 
-            if positions.is_empty() {
-                break;
-            }
+fn node_name(node: Line) -> [u8; 3] {
+    node.text.as_bytes().try_into().unwrap_or_default()
+}
 
-            for j in 0..positions.len() {
-                let this_node: Node = ptr::read(positions[j]);
+fn build_map(input: &str) -> Result<Network, Error> {
+    let nodes = lines(input)
+        .skip(1)
+        .filter(|l| !l.text.trim().is_empty())
+        .map(parse_node)
+        .collect::<Result<Vec<[Line; 3]>, Error>>()?;
 
-                match instructions[instruction_index] {
-                    b'L' => positions[j] = this_node.left,
-                    b'R' => positions[j] = this_node.right,
-                    _ => panic!("faulty instruction"),
-                }
-            }
-            num_steps += 1;
+    // Add every node before any edges, as edges can lead further down
+    let mut map = Network::default();
+    for [name, _, _] in nodes.iter() {
+        if map.id(&node_name(*name)).is_some() {
+            return Err(Error::Invalid {
+                line: name.number,
+                reason: "node is defined more than once",
+            });
         }
+        map.intern(node_name(*name));
     }
 
-    let mut lcm = num_steps_to_finish[0] as u64;
-    for i in 1..num_steps_to_finish.len() {
-        lcm = find_lcm(lcm, num_steps_to_finish[i] as u64);
+    let find = |map: &Network, node: Line| {
+        map.id(&node_name(node)).ok_or_else(|| Error::UnknownNode {
+            line: node.number,
+            name: node.text.to_string(),
+        })
+    };
+    for [name, left, right] in nodes.iter() {
+        let from = find(&map, *name)?;
+        let left = find(&map, *left)?;
+        let right = find(&map, *right)?;
+        map.add_edge(from, left);
+        map.add_edge(from, right);
     }
-
-    lcm
+    Ok(map)
 }
 
-fn find_gcd(a: u64, b: u64) -> u64 {
-    if b == 0 {
-        return a;
-    }
-    find_gcd(b, a % b)
-}
+/// Every ghost walks until its path loops, which can be both before and after
+/// reaching nodes ending in `Z`, and the ghosts' paths are then lined up to
+/// find the first step they're all on one.
+fn part_two(input: &str) -> Result<u64, Error> {
+    let instructions = parse_instructions(input)?;
+    let map = build_map(input)?;
 
-fn find_lcm(a: u64, b: u64) -> u64 {
-    if a | b == 0 {
-        return 0;
+    let orbits = map
+        .ids()
+        .filter(|&id| map.name(id)[2] == b'A')
+        .map(|start| ghost_orbit(&map, instructions, start))
+        .collect::<Vec<Orbit>>();
+    if orbits.is_empty() {
+        return Err(Error::NoAnswer("no nodes ending in `A` to start from"));
     }
-    a * (b / find_gcd(a, b))
+    cycle::first_common(&orbits).ok_or(Error::NoAnswer(
+        "the ghosts are never all on nodes ending in `Z` at once",
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The path from `start`, whose state is the node and how far through the
+/// instructions it is, hitting every node ending in `Z`.
+fn ghost_orbit(map: &Network, instructions: &[u8], start: NodeId) -> Orbit {
+    Orbit::trace(
+        (start, 0),
+        |&(node, i)| {
+            (
+                turn(map, node, instructions[i]),
+                (i + 1) % instructions.len(),
+            )
+        },
+        |&(node, _)| map.name(node)[2] == b'Z',
+    )
+}
 
-    const INPUT1: &str = "RL
+const INPUT1: &str = "RL
 
 AAA = (BBB, CCC)
 BBB = (DDD, EEE)
@@ -160,13 +193,13 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
 
-    const INPUT2: &str = "LLR
+const INPUT2: &str = "LLR
 
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
 
-    const INPUT3: &str = "LR
+const INPUT3: &str = "LR
 
 11A = (11B, XXX)
 11B = (XXX, 11Z)
@@ -177,30 +210,102 @@ ZZZ = (ZZZ, ZZZ)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT1), 2);
-        assert_eq!(part_one(INPUT2), 6);
+        assert_eq!(part_one(INPUT1), Ok(2));
+        assert_eq!(part_one(INPUT2), Ok(6));
     }
 
     #[test]
-    fn test_gcd() {
-        assert_eq!(find_gcd(0, 0), 0);
-        assert_eq!(find_gcd(10, 4), 2);
-        assert_eq!(find_gcd(13, 2), 1);
-        assert_eq!(find_gcd(24, 32), 8);
+    fn test_build_map() {
+        let map = build_map(INPUT2).unwrap();
+        let aaa = map.id(b"AAA").unwrap();
+        let bbb = map.id(b"BBB").unwrap();
+        let zzz = map.id(b"ZZZ").unwrap();
+        assert_eq!(map.neighbours(aaa), &[bbb, bbb]);
+        assert_eq!(turn(&map, bbb, b'L'), aaa);
+        assert_eq!(turn(&map, bbb, b'R'), zzz);
     }
 
     #[test]
-    fn test_lcm() {
-        assert_eq!(find_lcm(0, 0), 0);
-        assert_eq!(find_lcm(5, 2), 10);
-        assert_eq!(find_lcm(2, 5), 10);
-        assert_eq!(find_lcm(4, 6), 12);
+    fn test_part_two() {
+        assert_eq!(part_two(INPUT3), Ok(6));
+        // The ghosts reach `Z` nodes at different offsets into their loops,
+        // at 2, 4, 6, ... and at 1, 4, 7, ...
+        let offset = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22C, 22C)
+22C = (22Z, 22Z)";
+        assert_eq!(part_two(offset), Ok(4));
+        let never = "L
+
+11A = (11Z, 11Z)
+11Z = (11A, 11A)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        assert!(part_two(never).is_err());
     }
 
     #[test]
-    fn test_part_two() {
-        assert_eq!(part_two(INPUT3), 6);
+    fn test_ghost_orbit() {
+        let map = build_map(INPUT3).unwrap();
+        let start = map.id(b"22A").unwrap();
+        assert_eq!(
+            ghost_orbit(&map, b"LR", start),
+            Orbit {
+                start: 1,
+                length: 6,
+                hits: vec![3, 6]
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            build_map("LR\n\nAAA = (BBB, ZZZ)\nZZZ = (QQQ, ZZZ)").unwrap_err(),
+            Error::UnknownNode {
+                line: 3,
+                name: "BBB".to_string()
+            }
+        );
+        assert_eq!(
+            parse_instructions("LRX\n").unwrap_err().to_string(),
+            "line 1, column 3: unexpected character `X`"
+        );
+        assert!(matches!(
+            build_map("LR\n\nAAA (BBB, CCC)"),
+            Err(Error::Parse(_))
+        ));
+        assert_eq!(Day8.parse(INPUT1), Some(Ok(())));
+        assert!(matches!(
+            Day8.parse("LR\n\nAAA (BBB, CCC)"),
+            Some(Err(Error::Parse(_)))
+        ));
+        assert_eq!(
+            build_map("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)").unwrap_err(),
+            Error::Invalid {
+                line: 4,
+                reason: "node is defined more than once"
+            }
+        );
+        assert_eq!(
+            part_one("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)"),
+            Err(Error::NoAnswer("no `ZZZ` node to reach"))
+        );
+        assert_eq!(
+            part_one("LR\n\nAAA = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)"),
+            Err(Error::NoAnswer("`ZZZ` can't be reached from `AAA`"))
+        );
     }
 }
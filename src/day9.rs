@@ -1,28 +1,57 @@
-pub fn main() {
-    let input = std::fs::read_to_string("input/day9").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    println!("Part two: {}", part_two(&input));
-}
+use crate::error::Error;
+use crate::parsing::{lines, ParseError};
+use crate::solution::{Example, Part, Solution};
+use std::hint::black_box;
+
+pub struct Day9;
+impl Solution for Day9 {
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            part: Part::One,
+            input: INPUT,
+            answer: "114",
+        },
+        Example {
+            part: Part::Two,
+            input: INPUT,
+            answer: "2",
+        },
+    ];
+
+    fn part_one(&self, input: &str) -> Result<i64, Error> {
+        part_one(input)
+    }
+
+    fn part_two(&self, input: &str) -> Result<i64, Error> {
+        part_two(input)
+    }
 
-fn part_one(input: &str) -> i32 {
-    let mut history = vec![vec![]];
-    for l in input.lines() {
-        let mut this_line = vec![];
-        for digit in l.split_whitespace() {
-            this_line.push(digit.parse::<i32>().unwrap());
-        }
-        history.push(this_line);
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Some(black_box(parse_input(input)).map(drop).map_err(Error::from))
     }
+}
+
+fn part_one(input: &str) -> Result<i64, Error> {
+    let history = parse_input(input)?;
 
     let mut acc = 0;
     for h in history {
         acc += evaluate_line(&h);
     }
 
-    acc
+    Ok(acc)
+}
+
+fn parse_input(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    lines(input).map(|l| l.integers()).collect()
 }
 
-fn evaluate_line(vals: &[i32]) -> i32 {
+fn evaluate_line(vals: &[i64]) -> i64 {
     if vals.is_empty() {
         return 0;
     }
@@ -39,7 +68,7 @@ fn evaluate_line(vals: &[i32]) -> i32 {
     vals.last().unwrap() + evaluate_line(&diffs)
 }
 
-fn evaluate_line_two_sided(vals: &[i32]) -> (i32, i32) {
+fn evaluate_line_two_sided(vals: &[i64]) -> (i64, i64) {
     if vals.is_empty() {
         return (0, 0);
     }
@@ -60,15 +89,8 @@ fn evaluate_line_two_sided(vals: &[i32]) -> (i32, i32) {
     (first_val, last_val)
 }
 
-fn part_two(input: &str) -> i32 {
-    let mut history = vec![vec![]];
-    for l in input.lines() {
-        let mut this_line = vec![];
-        for digit in l.split_whitespace() {
-            this_line.push(digit.parse::<i32>().unwrap());
-        }
-        history.push(this_line);
-    }
+fn part_two(input: &str) -> Result<i64, Error> {
+    let history = parse_input(input)?;
 
     let mut acc = 0;
     for h in history {
@@ -76,20 +98,20 @@ fn part_two(input: &str) -> i32 {
         acc += left;
     }
 
-    acc
+    Ok(acc)
 }
 
+const INPUT: &str = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "0 3 6 9 12 15
-1 3 6 10 15 21
-10 13 16 21 30 45";
-
     #[test]
     fn test_part_one() {
-        assert_eq!(part_one(INPUT), 114);
+        assert_eq!(part_one(INPUT), Ok(114));
     }
 
     #[test]
@@ -108,6 +130,6 @@ mod tests {
 
     #[test]
     fn test_part_two() {
-        assert_eq!(part_two(INPUT), 2);
+        assert_eq!(part_two(INPUT), Ok(2));
     }
 }
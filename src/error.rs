@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use crate::parsing::ParseError;
+
+/// Why a day could not produce an answer. Problems with the input say which
+/// line they were found on.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Error {
+    Parse(ParseError),
+    Invalid { line: usize, reason: &'static str },
+    UnknownNode { line: usize, name: String },
+    NoAnswer(&'static str),
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "{e}"),
+            Error::Invalid { line, reason } => write!(f, "line {line}: {reason}"),
+            Error::UnknownNode { line, name } => write!(f, "line {line}: unknown node `{name}`"),
+            Error::NoAnswer(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
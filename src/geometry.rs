@@ -0,0 +1,147 @@
+// Tracing a polygon from moves is for dig plans, which no day reads yet
+#![cfg_attr(not(test), allow(dead_code))]
+
+use crate::math;
+use crate::pos::{Dir, Pos};
+
+/// Twice the area enclosed by a polygon whose vertices are given in order
+/// around it, in either direction. Kept doubled so that it stays exact, and
+/// `None` if it doesn't fit.
+pub fn twice_area(vertices: &[Pos]) -> Option<i64> {
+    // Each cross product fits in an i128, but summing them could overflow
+    // even that given enough edges
+    let sum = edges(vertices).try_fold(0i128, |sum, (a, b)| {
+        let cross = a.col as i128 * b.row as i128 - b.col as i128 * a.row as i128;
+        sum.checked_add(cross)
+    })?;
+    i64::try_from(sum.checked_abs()?).ok()
+}
+
+/// The number of lattice points on the polygon's edges, or `None` if it
+/// doesn't fit.
+pub fn boundary_points(vertices: &[Pos]) -> Option<i64> {
+    edges(vertices).try_fold(0i64, |sum, (a, b)| {
+        let row = b.row.abs_diff(a.row);
+        let col = b.col.abs_diff(a.col);
+        sum.checked_add(i64::try_from(math::gcd(row, col)).ok()?)
+    })
+}
+
+/// The number of lattice points strictly inside the polygon, by Pick's
+/// theorem: `A = i + b / 2 - 1`. `None` if the area doesn't fit.
+pub fn interior_points(vertices: &[Pos]) -> Option<i64> {
+    Some((twice_area(vertices)? - boundary_points(vertices)?) / 2 + 1)
+}
+
+/// The vertices visited by following moves of a direction and a length from
+/// the origin. The final move should lead back to the origin.
+pub fn trace<I: IntoIterator<Item = (Dir, i64)>>(moves: I) -> Vec<Pos> {
+    let mut pos = Pos::default();
+    let mut vertices = vec![pos];
+    for (dir, len) in moves {
+        pos += dir.delta() * len;
+        vertices.push(pos);
+    }
+    if vertices.len() > 1 && vertices.last() == vertices.first() {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Each edge of the polygon, closing it from the last vertex to the first.
+fn edges(vertices: &[Pos]) -> impl Iterator<Item = (Pos, Pos)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(side: i64) -> Vec<Pos> {
+        vec![
+            Pos::new(0, 0),
+            Pos::new(0, side),
+            Pos::new(side, side),
+            Pos::new(side, 0),
+        ]
+    }
+
+    #[test]
+    fn test_square() {
+        let square = square(4);
+        assert_eq!(twice_area(&square), Some(32));
+        assert_eq!(boundary_points(&square), Some(16));
+        assert_eq!(interior_points(&square), Some(9));
+        // Winding the other way doesn't change anything
+        let reversed = square.into_iter().rev().collect::<Vec<_>>();
+        assert_eq!(twice_area(&reversed), Some(32));
+    }
+
+    #[test]
+    fn test_triangle() {
+        // Diagonal edges only pass through some lattice points
+        let triangle = [Pos::new(0, 0), Pos::new(0, 4), Pos::new(2, 0)];
+        assert_eq!(twice_area(&triangle), Some(8));
+        assert_eq!(boundary_points(&triangle), Some(8));
+        assert_eq!(interior_points(&triangle), Some(1));
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(twice_area(&[]), Some(0));
+        assert_eq!(twice_area(&[Pos::new(3, 3)]), Some(0));
+        assert_eq!(boundary_points(&[Pos::new(0, 0), Pos::new(0, 5)]), Some(10));
+    }
+
+    #[test]
+    fn test_trace() {
+        let moves = [(Dir::E, 6), (Dir::S, 5), (Dir::W, 6), (Dir::N, 5)];
+        let vertices = trace(moves);
+        assert_eq!(
+            vertices,
+            vec![
+                Pos::new(0, 0),
+                Pos::new(0, 6),
+                Pos::new(5, 6),
+                Pos::new(5, 0)
+            ]
+        );
+        // Tiles dug out along a trench and inside it
+        let dug = interior_points(&vertices).zip(boundary_points(&vertices));
+        assert_eq!(dug.map(|(inside, edge)| inside + edge), Some(42));
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let side = 1_000_000_000;
+        let square = square(side);
+        assert_eq!(twice_area(&square), Some(2 * side * side));
+        assert_eq!(interior_points(&square), Some((side - 1) * (side - 1)));
+        let vertices = trace([
+            (Dir::E, side),
+            (Dir::S, side),
+            (Dir::W, side),
+            (Dir::N, side),
+        ]);
+        assert_eq!(interior_points(&vertices), Some((side - 1) * (side - 1)));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Far from the origin the cross products no longer fit in an i64,
+        // even though the area does
+        let offset = Pos::new(4_000_000_000, 4_000_000_000);
+        let far = square(10)
+            .into_iter()
+            .map(|p| p + offset)
+            .collect::<Vec<_>>();
+        assert_eq!(twice_area(&far), Some(200));
+        assert_eq!(interior_points(&far), Some(81));
+        assert_eq!(twice_area(&square(i64::MAX / 2)), None);
+        let line = [Pos::new(i64::MIN, 0), Pos::new(i64::MAX, 0)];
+        assert_eq!(boundary_points(&line), None);
+    }
+}
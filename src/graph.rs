@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A node of a `Graph`, only meaningful for the graph that handed it out.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// A directed graph whose nodes are kept in one arena and referred to by
+/// `NodeId`. Each node has a name that it can be looked up by, and an ordered
+/// list of the nodes its edges lead to.
+#[derive(Debug, Clone)]
+pub struct Graph<K> {
+    names: Vec<K>,
+    ids: HashMap<K, NodeId>,
+    edges: Vec<Vec<NodeId>>,
+}
+impl<K> Default for Graph<K> {
+    fn default() -> Self {
+        Self {
+            names: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+}
+impl<K: Hash + Eq + Clone> Graph<K> {
+    /// The node called `name`, added without edges if it isn't there yet.
+    pub fn intern(&mut self, name: K) -> NodeId {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+        let id = NodeId(self.names.len());
+        self.names.push(name.clone());
+        self.ids.insert(name, id);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, name: &K) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &K {
+        &self.names[id.0]
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        self.edges[from.0].push(to);
+    }
+
+    /// The nodes the edges from `id` lead to, in the order they were added.
+    pub fn neighbours(&self, id: NodeId) -> &[NodeId] {
+        &self.edges[id.0]
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.names.len()).map(NodeId)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut graph = Graph::default();
+        let a = graph.intern("a");
+        let b = graph.intern("b");
+        assert_ne!(a, b);
+        assert_eq!(graph.intern("a"), a);
+        assert_eq!(graph.len(), 2);
+        assert_eq!(graph.id(&"b"), Some(b));
+        assert_eq!(graph.id(&"c"), None);
+        assert_eq!(*graph.name(b), "b");
+        assert_eq!(graph.ids().collect::<Vec<_>>(), vec![a, b]);
+    }
+
+    #[test]
+    fn test_edges() {
+        let mut graph = Graph::default();
+        let a = graph.intern("a");
+        let b = graph.intern("b");
+        graph.add_edge(a, b);
+        graph.add_edge(a, a);
+        graph.add_edge(b, a);
+        assert_eq!(graph.neighbours(a), &[b, a]);
+        assert_eq!(graph.neighbours(b), &[a]);
+        let c = graph.intern("c");
+        assert!(graph.neighbours(c).is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+// Column and neighbour access are for grids no day has needed to read that
+// way yet
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::parsing::{self, ParseError};
+use crate::pos::{Dir, Pos};
+
+/// A rectangular grid stored row by row, indexed by `(row, column)`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+impl<T> Grid<T> {
+    /// Reads a grid from text, mapping each character with `cell`.
+    pub fn parse<F>(input: &str, cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = parsing::grid(input, cell)?;
+        let width = rows.first().map_or(0, |r| r.len());
+        let height = rows.len();
+        Ok(Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    /// Moves one step from `(row, col)` towards `dir`, if that stays on the
+    /// grid.
+    pub fn step(&self, (row, col): (usize, usize), dir: Dir) -> Option<(usize, usize)> {
+        let Pos { row: dr, col: dc } = dir.delta();
+        let row = row
+            .checked_add_signed(dr as isize)
+            .filter(|&r| r < self.height)?;
+        let col = col
+            .checked_add_signed(dc as isize)
+            .filter(|&c| c < self.width)?;
+        Some((row, col))
+    }
+
+    /// The up to 4 orthogonal neighbours that are on the grid.
+    pub fn neighbours4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Dir::CARDINAL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, d))
+    }
+
+    /// The up to 8 orthogonal and diagonal neighbours that are on the grid.
+    pub fn neighbours8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Dir::ALL.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width, which only an empty grid has
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// The first position, row by row, whose cell matches.
+    pub fn position<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<(usize, usize)> {
+        let i = self.cells.iter().position(predicate)?;
+        Some((i / self.width, i % self.width))
+    }
+
+    /// A view that repeats the grid endlessly in every direction.
+    pub fn tiled(&self) -> Tiled<'_, T> {
+        Tiled { grid: self }
+    }
+}
+
+/// The grid repeated endlessly in every direction, so that every `Pos` is on
+/// it. The original grid is the tile at `(0, 0)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tiled<'a, T> {
+    grid: &'a Grid<T>,
+}
+impl<'a, T> Tiled<'a, T> {
+    /// The position within the original grid that `pos` is a copy of.
+    pub fn wrap(&self, pos: Pos) -> (usize, usize) {
+        (
+            pos.row.rem_euclid(self.grid.height as i64) as usize,
+            pos.col.rem_euclid(self.grid.width as i64) as usize,
+        )
+    }
+
+    pub fn get(&self, pos: Pos) -> &'a T {
+        &self.grid[self.wrap(pos)]
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        self.get(row, col)
+            .unwrap_or_else(|| panic!("({row}, {col}) is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        self.get_mut(row, col)
+            .unwrap_or_else(|| panic!("({row}, {col}) is outside the grid"))
+    }
+}
+
+/// Writes the grid back out as text, one line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "#..\n.#.\n..S";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(INPUT, Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[(1, 1)], '#');
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.position(|&c| c == 'S'), Some((2, 2)));
+        assert_eq!(grid.to_string(), INPUT);
+        assert!(Grid::parse("..\n...", Some).is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours8((2, 2)).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1), (1, 1)]
+        );
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.step((1, 2), Dir::E), None);
+        assert_eq!(grid.step((1, 2), Dir::SW), Some((2, 1)));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::parse("ab\ncd\nef", Some).unwrap();
+        assert_eq!(grid.row(1), &['c', 'd']);
+        assert_eq!(grid.rows().count(), 3);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["ace", "bdf"]
+        );
+        assert_eq!(grid.positions().nth(3), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_tiled() {
+        let grid = grid();
+        let tiled = grid.tiled();
+        assert_eq!(*tiled.get(Pos::new(2, 2)), 'S');
+        assert_eq!(*tiled.get(Pos::new(-1, -1)), 'S');
+        assert_eq!(*tiled.get(Pos::new(7, -5)), '#');
+        assert_eq!(tiled.wrap(Pos::new(-4, 3)), (2, 0));
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut grid = grid();
+        grid[(0, 2)] = 'O';
+        *grid.get_mut(2, 0).unwrap() = 'O';
+        assert_eq!(grid.to_string(), "#.O\n.#.\nO.S");
+        assert!(grid.get_mut(0, 3).is_none());
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::transport::{Transport, TransportError};
+
+/// Environment variable that overrides where puzzle inputs are read from.
+pub const ROOT_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_ROOT: &str = "input";
+/// Passing this as a path reads the input from stdin instead.
+pub const STDIN: &str = "-";
+pub const BASE_URL: &str = "https://adventofcode.com/2023";
+/// File in the input root that records the checksum of every cached day.
+pub const CHECKSUMS: &str = "checksums";
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing {
+        day: u8,
+        tried: Vec<PathBuf>,
+    },
+    Unreadable {
+        day: u8,
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing { day, tried } => {
+                let tried = tried
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "no input for day {day}, expected {}", tried.join(" or "))
+            }
+            InputError::Unreadable { day, path, error } => write!(
+                f,
+                "could not read input for day {day} from {}: {error}",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Finds the puzzle input for a day below a root directory. Both `dayN` and
+/// `dayN.txt` are accepted, in that order.
+#[derive(Debug, PartialEq)]
+pub struct InputLoader {
+    root: PathBuf,
+}
+impl InputLoader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Uses `root` if given, then `AOC_INPUT_DIR`, then `input/`.
+    pub fn from_env(root: Option<PathBuf>) -> Self {
+        let root = root
+            .or_else(|| std::env::var_os(ROOT_VAR).map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_ROOT));
+        Self::new(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn candidates(&self, day: u8) -> [PathBuf; 2] {
+        [
+            self.root.join(format!("day{day}")),
+            self.root.join(format!("day{day}.txt")),
+        ]
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, InputError> {
+        let candidates = self.candidates(day);
+        match candidates.iter().find(|p| p.is_file()) {
+            Some(path) => read_path(day, path),
+            None => Err(InputError::Missing {
+                day,
+                tried: candidates.to_vec(),
+            }),
+        }
+    }
+}
+
+/// Reads the input for a day from an explicit path, or from stdin if the
+/// path is `-`.
+pub fn read_path(day: u8, path: &Path) -> Result<String, InputError> {
+    let unreadable = |error| InputError::Unreadable {
+        day,
+        path: path.to_path_buf(),
+        error,
+    };
+
+    if path == Path::new(STDIN) {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(unreadable)?;
+        return Ok(input);
+    }
+
+    match std::fs::read_to_string(path) {
+        Ok(input) => Ok(input),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(InputError::Missing {
+            day,
+            tried: vec![path.to_path_buf()],
+        }),
+        Err(e) => Err(unreadable(e)),
+    }
+}
+
+/// Reasons a downloaded or cached input can't be a puzzle input.
+#[derive(Debug, PartialEq)]
+pub enum Invalid {
+    Empty,
+    Html,
+    LoggedOut,
+}
+impl Display for Invalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Invalid::Empty => write!(f, "input is empty"),
+            Invalid::Html => write!(f, "input is an html page"),
+            Invalid::LoggedOut => write!(f, "input is the logged out message"),
+        }
+    }
+}
+
+pub fn validate(input: &str) -> Result<(), Invalid> {
+    let head = input.trim_start().to_ascii_lowercase();
+    if head.is_empty() {
+        Err(Invalid::Empty)
+    } else if head.starts_with("<!doctype") || head.starts_with("<html") {
+        Err(Invalid::Html)
+    } else if head.starts_with("puzzle inputs differ by user") {
+        Err(Invalid::LoggedOut)
+    } else {
+        Ok(())
+    }
+}
+
+/// 64 bit FNV-1a, which is stable across Rust versions unlike `DefaultHasher`.
+pub fn checksum(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Missing,
+    Invalid(Invalid),
+    /// The file is valid but has no recorded checksum.
+    Unrecorded,
+    /// The file no longer matches its recorded checksum.
+    Modified,
+    Ok,
+}
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Missing => write!(f, "missing"),
+            Status::Invalid(reason) => write!(f, "invalid, {reason}"),
+            Status::Unrecorded => write!(f, "no checksum recorded"),
+            Status::Modified => write!(f, "checksum mismatch"),
+            Status::Ok => write!(f, "ok"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CacheError {
+    NoSession,
+    Transport(TransportError),
+    Status(u16),
+    Invalid(u8, Invalid),
+    Io(PathBuf, std::io::Error),
+}
+impl Display for CacheError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheError::NoSession => write!(
+                f,
+                "no session cookie, put it in .session or set {SESSION_VAR}"
+            ),
+            CacheError::Transport(e) => write!(f, "{e}"),
+            CacheError::Status(status) => write!(f, "server responded with status {status}"),
+            CacheError::Invalid(day, reason) => write!(f, "day {day}: {reason}"),
+            CacheError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+/// Environment variable holding the session cookie, used if there is no
+/// `.session` file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+pub fn read_session() -> Result<String, CacheError> {
+    std::fs::read_to_string(".session")
+        .ok()
+        .or_else(|| std::env::var(SESSION_VAR).ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or(CacheError::NoSession)
+}
+
+/// Manages the downloaded inputs below an input root, together with the
+/// checksum recorded for each of them.
+pub struct Cache<'a> {
+    loader: &'a InputLoader,
+}
+impl<'a> Cache<'a> {
+    pub fn new(loader: &'a InputLoader) -> Self {
+        Self { loader }
+    }
+
+    fn checksums_path(&self) -> PathBuf {
+        self.loader.root().join(CHECKSUMS)
+    }
+
+    pub fn checksums(&self) -> BTreeMap<u8, u64> {
+        let contents = std::fs::read_to_string(self.checksums_path()).unwrap_or_default();
+        contents
+            .lines()
+            .filter_map(|l| {
+                let (day, sum) = l.split_once(' ')?;
+                let day = day.strip_prefix("day")?.parse::<u8>().ok()?;
+                let sum = u64::from_str_radix(sum.trim(), 16).ok()?;
+                Some((day, sum))
+            })
+            .collect()
+    }
+
+    fn write_checksums(&self, checksums: &BTreeMap<u8, u64>) -> Result<(), CacheError> {
+        let contents = checksums
+            .iter()
+            .map(|(day, sum)| format!("day{day} {sum:016x}\n"))
+            .collect::<String>();
+        let path = self.checksums_path();
+        std::fs::write(&path, contents).map_err(|e| CacheError::Io(path, e))
+    }
+
+    pub fn status(&self, day: u8) -> Status {
+        let input = match self.loader.load(day) {
+            Ok(input) => input,
+            Err(_) => return Status::Missing,
+        };
+        if let Err(reason) = validate(&input) {
+            return Status::Invalid(reason);
+        }
+        match self.checksums().get(&day) {
+            None => Status::Unrecorded,
+            Some(&sum) if sum == checksum(&input) => Status::Ok,
+            Some(_) => Status::Modified,
+        }
+    }
+
+    pub fn missing<I: IntoIterator<Item = u8>>(&self, days: I) -> Vec<u8> {
+        days.into_iter()
+            .filter(|&day| self.status(day) == Status::Missing)
+            .collect()
+    }
+
+    /// Validates and writes the input for a day, and records its checksum.
+    /// Nothing is written if the input is invalid.
+    pub fn store(&self, day: u8, input: &str) -> Result<PathBuf, CacheError> {
+        validate(input).map_err(|reason| CacheError::Invalid(day, reason))?;
+
+        let root = self.loader.root();
+        std::fs::create_dir_all(root).map_err(|e| CacheError::Io(root.to_path_buf(), e))?;
+        let [plain, txt] = self.loader.candidates(day);
+        let path = if txt.is_file() { txt } else { plain };
+        std::fs::write(&path, input).map_err(|e| CacheError::Io(path.clone(), e))?;
+
+        let mut checksums = self.checksums();
+        checksums.insert(day, checksum(input));
+        self.write_checksums(&checksums)?;
+        Ok(path)
+    }
+
+    pub fn fetch(
+        &self,
+        day: u8,
+        transport: &dyn Transport,
+        base_url: &str,
+        session: &str,
+    ) -> Result<PathBuf, CacheError> {
+        let url = format!("{base_url}/day/{day}/input");
+        let response = transport
+            .get(&url, session)
+            .map_err(CacheError::Transport)?;
+        if response.status != 200 {
+            return Err(CacheError::Status(response.status));
+        }
+        self.store(day, &response.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{serve_once, TempDir};
+
+    #[test]
+    fn test_load() {
+        let dir = TempDir::new("load");
+        let root = dir.path();
+        std::fs::write(root.join("day1"), "plain").unwrap();
+        std::fs::write(root.join("day3.txt"), "with extension").unwrap();
+
+        let loader = InputLoader::new(root);
+        assert_eq!(loader.load(1).unwrap(), "plain");
+        assert_eq!(loader.load(3).unwrap(), "with extension");
+    }
+
+    #[test]
+    fn test_missing() {
+        let loader = InputLoader::new("nowhere");
+        let error = loader.load(4).unwrap_err();
+        assert!(matches!(error, InputError::Missing { day: 4, .. }));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "no input for day 4, expected {} or {}",
+                Path::new("nowhere").join("day4").display(),
+                Path::new("nowhere").join("day4.txt").display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_from_env() {
+        assert_eq!(
+            InputLoader::from_env(Some(PathBuf::from("elsewhere"))),
+            InputLoader::new("elsewhere")
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(validate("1 2 3\n"), Ok(()));
+        assert_eq!(validate("  \n"), Err(Invalid::Empty));
+        assert_eq!(validate("<!DOCTYPE html>\n<html>"), Err(Invalid::Html));
+        assert_eq!(
+            validate("Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"),
+            Err(Invalid::LoggedOut)
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(""), 0xcbf29ce484222325);
+        assert_eq!(checksum("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_cache_status() {
+        let dir = TempDir::new("status");
+        let root = dir.path();
+        let loader = InputLoader::new(root);
+        let cache = Cache::new(&loader);
+        std::fs::write(root.join("day2"), "").unwrap();
+        std::fs::write(root.join("day3.txt"), "...*").unwrap();
+
+        assert_eq!(cache.status(1), Status::Missing);
+        assert_eq!(cache.status(2), Status::Invalid(Invalid::Empty));
+        assert_eq!(cache.status(3), Status::Unrecorded);
+        assert_eq!(cache.missing(1..=3), vec![1]);
+
+        assert_eq!(cache.store(3, "...*").unwrap(), root.join("day3.txt"));
+        assert_eq!(cache.status(3), Status::Ok);
+        std::fs::write(root.join("day3.txt"), "..*.").unwrap();
+        assert_eq!(cache.status(3), Status::Modified);
+
+        assert!(matches!(
+            cache.store(1, "<html></html>"),
+            Err(CacheError::Invalid(1, Invalid::Html))
+        ));
+        assert_eq!(cache.status(1), Status::Missing);
+    }
+
+    #[test]
+    fn test_fetch() {
+        use crate::transport::HttpTransport;
+
+        let dir = TempDir::new("fetch");
+        let root = dir.path();
+        let loader = InputLoader::new(root);
+        let cache = Cache::new(&loader);
+
+        let (base_url, server) = serve_once("200 OK", "0 3 6 9 12 15\n");
+        let path = cache.fetch(9, &HttpTransport, &base_url, "cookie").unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/9/input "));
+        assert!(request.contains("Cookie: session=cookie\r\n"));
+        assert_eq!(path, root.join("day9"));
+        assert_eq!(cache.status(9), Status::Ok);
+
+        let (base_url, server) = serve_once("400 Bad Request", "<html>nope</html>");
+        let result = cache.fetch(10, &HttpTransport, &base_url, "cookie");
+        server.join().unwrap();
+        assert!(matches!(result, Err(CacheError::Status(400))));
+
+        let (base_url, server) = serve_once("200 OK", "<!DOCTYPE html>");
+        let result = cache.fetch(10, &HttpTransport, &base_url, "cookie");
+        server.join().unwrap();
+        assert!(matches!(
+            result,
+            Err(CacheError::Invalid(10, Invalid::Html))
+        ));
+        assert_eq!(cache.status(10), Status::Missing);
+    }
+}
@@ -0,0 +1,230 @@
+// Day 5 only needs some of the set algebra, the rest is here for the next
+// puzzle that works with ranges
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::collections::BTreeMap;
+use std::ops::{Add, Range, Sub};
+
+/// A set of integers stored as sorted, half-open ranges. Touching or
+/// overlapping ranges are merged as they go in, so the set never holds more
+/// ranges than it needs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntervalSet<T> {
+    // Start of each range to its end
+    ranges: BTreeMap<T, T>,
+}
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+}
+impl<T: Copy + Ord> IntervalSet<T> {
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+        // Ends grow with starts, so the ranges to merge are a run that ends
+        // at the last one starting no later than `end`
+        let merged = self
+            .ranges
+            .range(..=end)
+            .rev()
+            .take_while(|(_, &e)| e >= start)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<(T, T)>>();
+        for (s, e) in merged {
+            self.ranges.remove(&s);
+            start = start.min(s);
+            end = end.max(e);
+        }
+        self.ranges.insert(start, end);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let cut = self
+            .ranges
+            .range(..range.end)
+            .rev()
+            .take_while(|(_, &e)| e > range.start)
+            .map(|(&s, &e)| (s, e))
+            .collect::<Vec<(T, T)>>();
+        for (s, e) in cut {
+            self.ranges.remove(&s);
+            if s < range.start {
+                self.ranges.insert(s, range.start);
+            }
+            if e > range.end {
+                self.ranges.insert(range.end, e);
+            }
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &e)| value < e)
+    }
+
+    /// The smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.keys().next().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The merged ranges, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.iter().map(|(&s, &e)| s..e)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut output = self.clone();
+        for range in other.iter() {
+            output.insert(range);
+        }
+        output
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut output = Self::default();
+        let (mut a, mut b) = (self.iter().peekable(), other.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            output.insert(x.start.max(y.start)..x.end.min(y.end));
+            // Whichever ends first can't overlap anything further on
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        output
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut output = self.clone();
+        for range in other.iter() {
+            output.remove(range);
+        }
+        output
+    }
+
+    /// The parts below `point`, and at or above it.
+    pub fn split_at(&self, point: T) -> (Self, Self) {
+        let (mut below, mut above) = (Self::default(), Self::default());
+        for range in self.iter() {
+            below.insert(range.start..range.end.min(point));
+            above.insert(range.start.max(point)..range.end);
+        }
+        (below, above)
+    }
+
+    /// How many values are in the set.
+    pub fn len(&self) -> T
+    where
+        T: Default + Add<Output = T> + Sub<Output = T>,
+    {
+        self.iter()
+            .fold(T::default(), |total, r| total + (r.end - r.start))
+    }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::default();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::default();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet<i64> {
+        ranges.iter().cloned().collect()
+    }
+
+    fn ranges(set: &IntervalSet<i64>) -> Vec<Range<i64>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let mut s = set(&[10..20, 30..40]);
+        s.insert(20..25);
+        assert_eq!(ranges(&s), vec![10..25, 30..40]);
+        s.insert(5..35);
+        assert_eq!(ranges(&s), vec![5..40]);
+        s.insert(50..50);
+        assert_eq!(ranges(&s), vec![5..40]);
+        s.insert(-10..-5);
+        assert_eq!(ranges(&s), vec![-10..-5, 5..40]);
+        assert_eq!(s.len(), 40);
+        assert!(s.contains(-10));
+        assert!(!s.contains(-5));
+        assert!(!s.contains(0));
+        assert!(s.contains(39));
+        assert_eq!(s.min(), Some(-10));
+        assert_eq!(IntervalSet::<i64>::default().min(), None);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut s = set(&[0..10, 20..30]);
+        s.remove(5..25);
+        assert_eq!(ranges(&s), vec![0..5, 25..30]);
+        s.remove(1..2);
+        assert_eq!(ranges(&s), vec![0..1, 2..5, 25..30]);
+        s.remove(-100..100);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..10, 20..30]);
+        let b = set(&[5..25, 28..40]);
+        assert_eq!(ranges(&a.union(&b)), vec![0..40]);
+        assert_eq!(ranges(&a.intersection(&b)), vec![5..10, 20..25, 28..30]);
+        assert_eq!(ranges(&a.difference(&b)), vec![0..5, 25..28]);
+        assert_eq!(ranges(&b.difference(&a)), vec![10..20, 30..40]);
+        assert!(a.intersection(&IntervalSet::from(10..20)).is_empty());
+    }
+
+    #[test]
+    fn test_split_at() {
+        let (below, above) = set(&[0..10, 20..30]).split_at(25);
+        assert_eq!(ranges(&below), vec![0..10, 20..25]);
+        assert_eq!(ranges(&above), vec![25..30]);
+        let (below, above) = IntervalSet::from(0..10).split_at(10);
+        assert_eq!(ranges(&below), vec![0..10]);
+        assert!(above.is_empty());
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        let s: IntervalSet<u64> = [0..u64::MAX / 2, u64::MAX / 2..u64::MAX]
+            .into_iter()
+            .collect();
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0..u64::MAX]);
+        assert_eq!(s.len(), u64::MAX);
+    }
+}
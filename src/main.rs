@@ -1,8 +1,18 @@
 use std::env;
+use std::process::ExitCode;
 
+use cli::Command;
+use inputs::InputLoader;
+
+mod answer;
+mod answers;
+mod bench;
+mod cli;
+mod cycle;
 mod day1;
 mod day10;
 mod day2;
+mod day21;
 mod day3;
 mod day4;
 mod day5;
@@ -10,32 +20,79 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod error;
+mod geometry;
+mod graph;
+mod grid;
+mod inputs;
+mod interval;
+mod math;
 mod parsing;
+mod pos;
+mod reach;
+mod registry;
+mod runner;
+mod scaffold;
+mod search;
+mod solution;
+#[cfg(test)]
+mod testing;
+mod transport;
 
-fn main() {
-    let entry_points = [
-        day1::main,
-        day2::main,
-        day3::main,
-        day4::main,
-        day5::main,
-        day6::main,
-        day7::main,
-        day8::main,
-        day9::main,
-        day10::main,
-    ];
-
-    let args: Vec<String> = env::args().collect();
-    let mut index = entry_points.len() - 1;
+fn main() -> ExitCode {
+    let args = match cli::parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {e}\nRun `aoc2023 help` for usage");
+            return ExitCode::FAILURE;
+        }
+    };
 
-    if args.len() > 1 {
-        if let Ok(day) = args[1].as_str().parse::<usize>() {
-            index = day - 1;
+    let loader = InputLoader::from_env(args.input_dir);
+    let result = match args.command {
+        Command::Run {
+            target,
+            part,
+            record,
+        } => runner::run(target, part, record, &args.source, &loader),
+        Command::Verify => runner::verify(&loader),
+        Command::List => {
+            runner::list();
+            Ok(())
+        }
+        Command::Test(solution) => runner::test(solution),
+        Command::Bench {
+            solution,
+            part,
+            options,
+            baseline,
+            save_baseline,
+        } => runner::bench(
+            solution,
+            part,
+            options,
+            &baseline,
+            save_baseline,
+            &args.source,
+            &loader,
+        ),
+        Command::Inputs(command) => runner::inputs(command, &loader),
+        Command::New {
+            day,
+            title,
+            example,
+        } => runner::new_day(day, title.as_deref(), example.as_deref()),
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
         }
-    } else {
-        println!("No day specified, running latest");
     };
 
-    entry_points[index]()
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
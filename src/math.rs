@@ -0,0 +1,227 @@
+// Modular inverses, powers and the lcm of many numbers are here for puzzles
+// that haven't come up yet
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::ops::{Div, Rem};
+
+/// The integer types the number theory helpers work on.
+pub trait Number: Copy + PartialEq + PartialOrd + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn abs(self) -> Self;
+}
+impl Number for u64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        u64::checked_mul(self, rhs)
+    }
+    fn abs(self) -> Self {
+        self
+    }
+}
+impl Number for i128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        i128::checked_mul(self, rhs)
+    }
+    fn abs(self) -> Self {
+        i128::abs(self)
+    }
+}
+
+/// The greatest common divisor, never negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Number>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// The least common multiple, never negative, or `None` if it doesn't fit.
+pub fn lcm<T: Number>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / gcd(a, b)).checked_mul(b).map(T::abs)
+}
+
+/// The least common multiple of every number, which is 1 if there are none.
+pub fn lcm_all<T: Number, I: IntoIterator<Item = T>>(numbers: I) -> Option<T> {
+    numbers.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` where `g = gcd(a, b) = a * x + b * y`.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = ext_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base` to the power `exp`, modulo `m`.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let m = m as u128;
+    let mut base = base as u128 % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result as u64
+}
+
+/// The largest `r` with `r * r <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above, starting at a power of two that's at least
+    // the root
+    let mut x = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A xorshift generator, so the properties below are checked against the
+    /// same spread of numbers every run.
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Mostly small numbers, which share more factors, and some huge ones.
+        fn number(&mut self) -> u64 {
+            match self.next() % 4 {
+                0 => self.next(),
+                1 => self.next() % 1_000_000_007,
+                _ => self.next() % 1000,
+            }
+        }
+    }
+
+    const CASES: usize = 10_000;
+
+    #[test]
+    fn test_examples() {
+        assert_eq!(gcd(0u64, 0), 0);
+        assert_eq!(gcd(24u64, 32), 8);
+        assert_eq!(gcd(-24i128, 32), 8);
+        assert_eq!(lcm(4u64, 6), Some(12));
+        assert_eq!(lcm(-4i128, 6), Some(12));
+        assert_eq!(lcm(0u64, 5), Some(0));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all::<u64, _>([]), Some(1));
+        assert_eq!(mod_inv(3, 7), Some(5));
+        assert_eq!(mod_inv(-3, 7), Some(2));
+        assert_eq!(mod_inv(4, 8), None);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_gcd_lcm_properties() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..CASES {
+            let (a, b) = (rng.number(), rng.number());
+            let g = gcd(a, b);
+            assert_eq!(g, gcd(b, a));
+            if g != 0 {
+                assert_eq!((a % g, b % g), (0, 0));
+                assert_eq!(gcd(a / g, b / g), 1);
+            }
+            let product = a as u128 * b as u128;
+            let expected = if g == 0 { 0 } else { product / g as u128 };
+            assert_eq!(lcm(a, b), u64::try_from(expected).ok());
+            assert_eq!(gcd(a as i128, -(b as i128)), g as i128);
+        }
+    }
+
+    #[test]
+    fn test_ext_gcd_properties() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..CASES {
+            let a = rng.number() as i128 - rng.number() as i128;
+            let b = rng.number() as i128 - rng.number() as i128;
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+
+            let m = (b.abs() % 1_000_000_007).max(2);
+            match mod_inv(a, m) {
+                Some(inv) => {
+                    assert!((0..m).contains(&inv));
+                    assert_eq!((a.rem_euclid(m) * inv) % m, 1);
+                }
+                None => assert_ne!(gcd(a, m), 1),
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_pow_properties() {
+        let mut rng = Rng(0xdead_beef_cafe_f00d);
+        for _ in 0..CASES {
+            let (base, exp, m) = (rng.next(), rng.next() % 64, rng.number().max(1));
+            let expected =
+                (0..exp).fold(1 % m as u128, |acc, _| acc * (base % m) as u128 % m as u128);
+            assert_eq!(mod_pow(base, exp, m) as u128, expected);
+            // Fermat's little theorem
+            assert_eq!(
+                mod_pow(base % 1_000_000_006 + 1, 1_000_000_006, 1_000_000_007),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn test_isqrt_properties() {
+        let mut rng = Rng(0x0123_4567_89ab_cdef);
+        for _ in 0..CASES {
+            let n = (rng.next() as u128) << (rng.next() % 64) | rng.number() as u128;
+            let r = isqrt(n);
+            assert!(r * r <= n);
+            assert!((r + 1).checked_mul(r + 1).is_none_or(|s| s > n));
+            assert_eq!(isqrt(r * r), r);
+        }
+    }
+}
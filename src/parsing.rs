@@ -1,5 +1,464 @@
-pub fn split_once<'a>(input: &'a str, pat: char) -> (&'a str, &'a str) {
-    let mid = input.find(pat).unwrap_or(input.len());
-    let (head, tail) = input.split_at(mid);
-    (&head[..mid], &tail[1..])
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ErrorKind {
+    Expected(char),
+    InvalidInteger(String),
+    MissingKey(String),
+    DuplicateKey(String),
+    UnexpectedChar(char),
+    Unexpected(String),
+    WrongCount { expected: usize, found: usize },
+    RaggedRow { expected: usize, found: usize },
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::Expected(c) => write!(f, "expected `{c}`"),
+            ErrorKind::InvalidInteger(s) => write!(f, "invalid integer `{s}`"),
+            ErrorKind::MissingKey(key) => write!(f, "missing `{key}:` line"),
+            ErrorKind::DuplicateKey(key) => write!(f, "`{key}:` appears more than once"),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character `{c}`"),
+            ErrorKind::Unexpected(s) => write!(f, "unexpected `{s}`"),
+            ErrorKind::WrongCount { expected, found } => {
+                write!(f, "expected {expected} integers, found {found}")
+            }
+            ErrorKind::RaggedRow { expected, found } => {
+                write!(f, "row has {found} cells, expected {expected}")
+            }
+        }
+    }
+}
+
+/// A parse failure, with 1-based line and column numbers.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.kind
+        )
+    }
+}
+
+/// Integers that `Line::integers` can extract. Only signed types pick up a
+/// leading `-`.
+pub trait Integer: FromStr + Copy {
+    const SIGNED: bool;
+}
+macro_rules! impl_integer {
+    ($signed:expr, $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+            }
+        )*
+    };
+}
+impl_integer!(true, i8, i16, i32, i64, i128, isize);
+impl_integer!(false, u8, u16, u32, u64, u128, usize);
+
+/// Part of one line of input that remembers where it starts, so errors can
+/// point at the offending text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub column: usize,
+    pub text: &'a str,
+}
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Self {
+            number,
+            column: 1,
+            text,
+        }
+    }
+
+    /// An error pointing at the start of this text.
+    pub fn error(&self, kind: ErrorKind) -> ParseError {
+        self.error_at(0, kind)
+    }
+
+    /// An error pointing `offset` bytes into this text.
+    pub fn error_at(&self, offset: usize, kind: ErrorKind) -> ParseError {
+        ParseError {
+            line: self.number,
+            column: self.column + self.text[..offset].chars().count(),
+            kind,
+        }
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Line<'a> {
+        Line {
+            number: self.number,
+            column: self.column + self.text[..start].chars().count(),
+            text: &self.text[start..end],
+        }
+    }
+
+    pub fn trim(&self) -> Line<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len();
+        self.slice(start, end.max(start))
+    }
+
+    /// Splits around the first `pat`, failing at the end of the line if there
+    /// is none.
+    pub fn split_once(&self, pat: char) -> Result<(Line<'a>, Line<'a>), ParseError> {
+        let mid = self
+            .text
+            .find(pat)
+            .ok_or_else(|| self.error_at(self.text.len(), ErrorKind::Expected(pat)))?;
+        Ok((
+            self.slice(0, mid),
+            self.slice(mid + pat.len_utf8(), self.text.len()),
+        ))
+    }
+
+    /// The pieces between each `pat`.
+    pub fn split(&self, pat: char) -> impl Iterator<Item = Line<'a>> + '_ {
+        self.text.split(pat).map(|piece| {
+            let start = piece.as_ptr() as usize - self.text.as_ptr() as usize;
+            self.slice(start, start + piece.len())
+        })
+    }
+
+    /// Whitespace separated fields.
+    pub fn fields(&self) -> impl Iterator<Item = Line<'a>> + '_ {
+        self.text.split_whitespace().map(|field| {
+            let start = field.as_ptr() as usize - self.text.as_ptr() as usize;
+            self.slice(start, start + field.len())
+        })
+    }
+
+    /// Parses the whole trimmed text as one integer.
+    pub fn integer<T: Integer>(&self) -> Result<T, ParseError> {
+        let trimmed = self.trim();
+        trimmed
+            .text
+            .parse()
+            .map_err(|_| trimmed.error(ErrorKind::InvalidInteger(trimmed.text.to_string())))
+    }
+
+    /// Every run of digits in the text, ignoring whatever separates them.
+    pub fn integers<T: Integer>(&self) -> Result<Vec<T>, ParseError> {
+        let bytes = self.text.as_bytes();
+        let mut output = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let negative = T::SIGNED
+                && bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit());
+            if !negative && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            output.push(self.slice(start, i).integer()?);
+        }
+        Ok(output)
+    }
+
+    /// Exactly `N` integers, as extracted by `integers`.
+    pub fn array<T: Integer, const N: usize>(&self) -> Result<[T; N], ParseError> {
+        let integers = self.integers()?;
+        let found = integers.len();
+        integers
+            .try_into()
+            .map_err(|_| self.error(ErrorKind::WrongCount { expected: N, found }))
+    }
+}
+
+/// Numbered lines of the input.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line::new(i + 1, text))
+}
+
+/// A run of lines introduced by a `label: header` line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub label: Line<'a>,
+    pub header: Line<'a>,
+    pub lines: Vec<Line<'a>>,
+}
+
+/// Splits blank-line separated sections into labelled blocks, like
+/// `seeds: 79 14` or `seed-to-soil map:` followed by its rows.
+pub fn blocks(input: &str) -> Result<Vec<Block<'_>>, ParseError> {
+    let mut output = vec![];
+    let mut lines = lines(input).peekable();
+    while let Some(first) = lines.next() {
+        if first.text.trim().is_empty() {
+            continue;
+        }
+        let (label, header) = first.split_once(':')?;
+        let mut block = Block {
+            label: label.trim(),
+            header: header.trim(),
+            lines: vec![],
+        };
+        while let Some(line) = lines.next_if(|l| !l.text.trim().is_empty()) {
+            block.lines.push(line);
+        }
+        output.push(block);
+    }
+    Ok(output)
+}
+
+/// Values of `key: value` lines, looked up by key.
+#[derive(Debug)]
+pub struct Headers<'a> {
+    entries: Vec<(&'a str, Line<'a>)>,
+    end: usize,
+}
+impl<'a> Headers<'a> {
+    pub fn get(&self, key: &str) -> Result<Line<'a>, ParseError> {
+        self.entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| ParseError {
+                line: self.end,
+                column: 1,
+                kind: ErrorKind::MissingKey(key.to_string()),
+            })
+    }
+}
+
+/// Reads every non-blank line as a `key: value` header, e.g. `Time: 7 15 30`.
+pub fn headers(input: &str) -> Result<Headers<'_>, ParseError> {
+    let mut entries: Vec<(&str, Line)> = vec![];
+    let mut end = 1;
+    for line in lines(input) {
+        end = line.number + 1;
+        if line.text.trim().is_empty() {
+            continue;
+        }
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim();
+        if entries.iter().any(|(k, _)| *k == key.text) {
+            return Err(key.error(ErrorKind::DuplicateKey(key.text.to_string())));
+        }
+        entries.push((key.text, value.trim()));
+    }
+    Ok(Headers { entries, end })
+}
+
+/// Reads a rectangular block of characters, mapping each one with `cell`.
+/// Characters `cell` rejects and rows of the wrong length are errors.
+pub fn grid<T, F>(input: &str, mut cell: F) -> Result<Vec<Vec<T>>, ParseError>
+where
+    F: FnMut(char) -> Option<T>,
+{
+    let mut rows: Vec<Vec<T>> = vec![];
+    for line in lines(input.trim_end_matches(['\n', '\r'])) {
+        let row = line
+            .text
+            .char_indices()
+            .map(|(i, c)| cell(c).ok_or_else(|| line.error_at(i, ErrorKind::UnexpectedChar(c))))
+            .collect::<Result<Vec<T>, ParseError>>()?;
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                // Point at the first cell too many, or the end of a short row
+                let offset = line
+                    .text
+                    .char_indices()
+                    .nth(first.len())
+                    .map_or(line.text.len(), |(i, _)| i);
+                return Err(line.error_at(
+                    offset,
+                    ErrorKind::RaggedRow {
+                        expected: first.len(),
+                        found: row.len(),
+                    },
+                ));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_once() {
+        let line = Line::new(3, "Card 1: 41 48 | 83 86");
+        let (head, tail) = line.split_once(':').unwrap();
+        assert_eq!(head.text, "Card 1");
+        assert_eq!((tail.column, tail.text), (8, " 41 48 | 83 86"));
+        let (_, tail) = tail.split_once('|').unwrap();
+        assert_eq!(tail.trim().column, 17);
+
+        assert_eq!(
+            Line::new(2, "no colon").split_once(':'),
+            Err(ParseError {
+                line: 2,
+                column: 9,
+                kind: ErrorKind::Expected(':')
+            })
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let line = Line::new(1, "3 blue; 1 red");
+        assert_eq!(
+            line.split(';')
+                .map(|l| (l.column, l.text))
+                .collect::<Vec<_>>(),
+            vec![(1, "3 blue"), (8, " 1 red")]
+        );
+    }
+
+    #[test]
+    fn test_integers() {
+        let line = Line::new(1, "x=-3, y=12..-7");
+        assert_eq!(line.integers::<i32>().unwrap(), vec![-3, 12, -7]);
+        assert_eq!(line.integers::<u32>().unwrap(), vec![3, 12, 7]);
+        assert_eq!(
+            Line::new(1, "Game 12: 3 blue").integers::<u8>().unwrap(),
+            vec![12, 3]
+        );
+        assert_eq!(
+            Line::new(4, "1 2 300").integers::<u8>(),
+            Err(ParseError {
+                line: 4,
+                column: 5,
+                kind: ErrorKind::InvalidInteger("300".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_array() {
+        assert_eq!(Line::new(1, "50 98 2").array::<u32, 3>(), Ok([50, 98, 2]));
+        assert_eq!(
+            Line::new(1, "50 98").array::<u32, 3>().unwrap_err().kind,
+            ErrorKind::WrongCount {
+                expected: 3,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_integer() {
+        assert_eq!(Line::new(1, "  42 ").integer::<u64>(), Ok(42));
+        assert_eq!(
+            Line::new(1, "ab 4x")
+                .fields()
+                .nth(1)
+                .unwrap()
+                .integer::<u64>(),
+            Err(ParseError {
+                line: 1,
+                column: 4,
+                kind: ErrorKind::InvalidInteger("4x".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input =
+            "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\nsoil map:\n0 15 37\n";
+        let blocks = blocks(input).unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].label.text, "seeds");
+        assert_eq!(blocks[0].header.integers::<u32>().unwrap(), vec![79, 14]);
+        assert!(blocks[0].lines.is_empty());
+        assert_eq!(blocks[1].label.text, "seed-to-soil map");
+        assert_eq!(
+            blocks[1].lines.iter().map(|l| l.number).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        assert_eq!(blocks[2].lines[0].text, "0 15 37");
+
+        assert_eq!(super::blocks("a: 1\n\nb\n").unwrap_err().line, 3);
+    }
+
+    #[test]
+    fn test_headers() {
+        let headers = headers("Time:      7  15\nDistance:  9  40\n").unwrap();
+        assert_eq!(
+            headers.get("Time").unwrap().integers::<u64>().unwrap(),
+            vec![7, 15]
+        );
+        assert_eq!(headers.get("Distance").unwrap().column, 12);
+        assert_eq!(
+            headers.get("Speed"),
+            Err(ParseError {
+                line: 3,
+                column: 1,
+                kind: ErrorKind::MissingKey("Speed".to_string())
+            })
+        );
+        assert_eq!(
+            super::headers("a: 1\na: 2").unwrap_err().kind,
+            ErrorKind::DuplicateKey("a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let wall = |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        };
+        assert_eq!(
+            grid("#.\n.#\n", wall).unwrap(),
+            vec![vec![true, false], vec![false, true]]
+        );
+        assert_eq!(
+            grid("#.\n.x", wall),
+            Err(ParseError {
+                line: 2,
+                column: 2,
+                kind: ErrorKind::UnexpectedChar('x')
+            })
+        );
+        assert_eq!(
+            grid("#.\n.#.", wall),
+            Err(ParseError {
+                line: 2,
+                column: 3,
+                kind: ErrorKind::RaggedRow {
+                    expected: 2,
+                    found: 3
+                }
+            })
+        );
+        // Columns count characters, not bytes
+        assert_eq!(
+            grid("..\n€", Some),
+            Err(ParseError {
+                line: 2,
+                column: 2,
+                kind: ErrorKind::RaggedRow {
+                    expected: 2,
+                    found: 1
+                }
+            })
+        );
+        assert_eq!(grid("..\n€€€", Some).unwrap_err().column, 3);
+    }
 }
@@ -0,0 +1,212 @@
+// Distances and turning round out the types before any day measures or turns
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// A position on an unbounded grid, or the offset between two of them. Rows
+/// grow downwards, as they do when reading a puzzle input.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: i64,
+    pub col: i64,
+}
+impl Pos {
+    pub fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    /// Distance when moving only orthogonally.
+    pub fn manhattan(self, other: Pos) -> u64 {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    /// Distance when diagonal moves are allowed too.
+    pub fn chebyshev(self, other: Pos) -> u64 {
+        self.row
+            .abs_diff(other.row)
+            .max(self.col.abs_diff(other.col))
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl Add<Dir> for Pos {
+    type Output = Pos;
+    fn add(self, rhs: Dir) -> Self::Output {
+        self + rhs.delta()
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+    fn neg(self) -> Self::Output {
+        Self::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<i64> for Pos {
+    type Output = Pos;
+    fn mul(self, rhs: i64) -> Self::Output {
+        Self::new(self.row * rhs, self.col * rhs)
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row as i64, col as i64)
+    }
+}
+
+/// A compass direction, with north pointing up the page.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Dir {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+impl Dir {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Dir; 8] = [
+        Dir::N,
+        Dir::NE,
+        Dir::E,
+        Dir::SE,
+        Dir::S,
+        Dir::SW,
+        Dir::W,
+        Dir::NW,
+    ];
+    /// The orthogonal directions, clockwise from north.
+    pub const CARDINAL: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
+
+    /// The offset of one step in this direction.
+    pub fn delta(self) -> Pos {
+        match self {
+            Dir::N => Pos::new(-1, 0),
+            Dir::NE => Pos::new(-1, 1),
+            Dir::E => Pos::new(0, 1),
+            Dir::SE => Pos::new(1, 1),
+            Dir::S => Pos::new(1, 0),
+            Dir::SW => Pos::new(1, -1),
+            Dir::W => Pos::new(0, -1),
+            Dir::NW => Pos::new(-1, -1),
+        }
+    }
+
+    /// Turns by `eighths` of a full turn clockwise.
+    fn rotate(self, eighths: usize) -> Dir {
+        Dir::ALL[(self as usize + eighths) % 8]
+    }
+
+    pub fn opposite(self) -> Dir {
+        self.rotate(4)
+    }
+}
+
+impl Dir {
+    /// Turns a quarter turn anticlockwise.
+    pub fn turn_left(self) -> Dir {
+        self.rotate(6)
+    }
+
+    /// Turns a quarter turn clockwise.
+    pub fn turn_right(self) -> Dir {
+        self.rotate(2)
+    }
+
+    /// Reads one of the arrows `^>v<`.
+    pub fn from_arrow(c: char) -> Option<Dir> {
+        match c {
+            '^' => Some(Dir::N),
+            '>' => Some(Dir::E),
+            'v' => Some(Dir::S),
+            '<' => Some(Dir::W),
+            _ => None,
+        }
+    }
+
+    /// The arrow pointing this way, for the orthogonal directions.
+    pub fn arrow(self) -> Option<char> {
+        match self {
+            Dir::N => Some('^'),
+            Dir::E => Some('>'),
+            Dir::S => Some('v'),
+            Dir::W => Some('<'),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Pos::new(2, -3);
+        let b = Pos::new(-1, 5);
+        assert_eq!(a + b, Pos::new(1, 2));
+        assert_eq!(a - b, Pos::new(3, -8));
+        assert_eq!(-a, Pos::new(-2, 3));
+        assert_eq!(a * 3, Pos::new(6, -9));
+        assert_eq!(a + Dir::SE, Pos::new(3, -2));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Pos::new(2, -3);
+        let b = Pos::new(-1, 5);
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 8);
+        assert_eq!(b.manhattan(b), 0);
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Dir::N.turn_right(), Dir::E);
+        assert_eq!(Dir::N.turn_left(), Dir::W);
+        assert_eq!(Dir::NE.turn_right(), Dir::SE);
+        assert_eq!(Dir::SW.turn_left(), Dir::SE);
+        assert_eq!(Dir::E.opposite(), Dir::W);
+        assert_eq!(Dir::NW.opposite(), Dir::SE);
+        for dir in Dir::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.opposite().delta(), -dir.delta());
+        }
+    }
+
+    #[test]
+    fn test_arrows() {
+        for dir in Dir::CARDINAL {
+            assert_eq!(dir.arrow().and_then(Dir::from_arrow), Some(dir));
+        }
+        assert_eq!(Dir::from_arrow('x'), None);
+        assert_eq!(Dir::NE.arrow(), None);
+    }
+}
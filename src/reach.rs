@@ -0,0 +1,177 @@
+// Looking up single cells is mostly for checking results in the tests
+#![cfg_attr(not(test), allow(dead_code))]
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A grid of bits packed 64 columns to a word, each row starting on a new
+/// word.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(WORD_BITS);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        row < self.height
+            && col < self.width
+            && self.words[row * self.stride + col / WORD_BITS] & (1 << (col % WORD_BITS)) != 0
+    }
+
+    pub fn set(&mut self, row: usize, col: usize) {
+        assert!(row < self.height && col < self.width);
+        self.words[row * self.stride + col / WORD_BITS] |= 1 << (col % WORD_BITS);
+    }
+}
+
+/// Steps a set of cells to their orthogonal neighbours over and over, the
+/// way a walker who must move every step spreads out. The frontier holds
+/// every cell the walker can be on after the steps taken so far.
+#[derive(Debug)]
+pub struct Reachability {
+    open: BitGrid,
+    frontier: BitGrid,
+    next: BitGrid,
+    // Bounding box of the frontier, as half-open row and word ranges
+    rows: (usize, usize),
+    words: (usize, usize),
+}
+impl Reachability {
+    /// Starts from `start` on a `width` by `height` grid, where `open` says
+    /// which cells can be stepped on.
+    pub fn new<F>(width: usize, height: usize, open: F, start: (usize, usize)) -> Self
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let mut open_cells = BitGrid::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                if open(row, col) {
+                    open_cells.set(row, col);
+                }
+            }
+        }
+        let mut frontier = BitGrid::new(width, height);
+        frontier.set(start.0, start.1);
+        let word = start.1 / WORD_BITS;
+        Self {
+            next: frontier.clone(),
+            open: open_cells,
+            frontier,
+            rows: (start.0, start.0 + 1),
+            words: (word, word + 1),
+        }
+    }
+
+    /// Moves every cell of the frontier one step in each direction, keeping
+    /// the ones that land on open cells.
+    pub fn step(&mut self) {
+        let BitGrid { height, stride, .. } = self.frontier;
+        self.rows = (self.rows.0.saturating_sub(1), (self.rows.1 + 1).min(height));
+        self.words = (
+            self.words.0.saturating_sub(1),
+            (self.words.1 + 1).min(stride),
+        );
+
+        let current = &self.frontier.words;
+        for row in self.rows.0..self.rows.1 {
+            for word in self.words.0..self.words.1 {
+                let i = row * stride + word;
+                let here = current[i];
+                let mut bits = (here << 1) | (here >> 1);
+                if word > 0 {
+                    bits |= current[i - 1] >> (WORD_BITS - 1);
+                }
+                if word + 1 < stride {
+                    bits |= current[i + 1] << (WORD_BITS - 1);
+                }
+                if row > 0 {
+                    bits |= current[i - stride];
+                }
+                if row + 1 < height {
+                    bits |= current[i + stride];
+                }
+                self.next.words[i] = bits & self.open.words[i];
+            }
+        }
+        std::mem::swap(&mut self.frontier, &mut self.next);
+    }
+
+    /// Number of cells in the frontier. Only the bounding box is counted, as
+    /// the rest is known to be empty.
+    pub fn count(&self) -> u64 {
+        let stride = self.frontier.stride;
+        (self.rows.0..self.rows.1)
+            .flat_map(|row| &self.frontier.words[row * stride..][self.words.0..self.words.1])
+            .map(|w| w.count_ones() as u64)
+            .sum()
+    }
+
+    /// The number of cells reachable in exactly `k` steps, for every `k` from
+    /// 0 to `steps`.
+    pub fn counts(mut self, steps: usize) -> Vec<u64> {
+        let mut counts = Vec::with_capacity(steps + 1);
+        counts.push(self.count());
+        for _ in 0..steps {
+            self.step();
+            counts.push(self.count());
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_grid() {
+        let mut grid = BitGrid::new(130, 2);
+        grid.set(0, 0);
+        grid.set(1, 63);
+        grid.set(1, 64);
+        grid.set(1, 129);
+        assert!(grid.get(1, 64));
+        assert!(!grid.get(0, 1));
+        assert!(!grid.get(2, 0));
+    }
+
+    #[test]
+    fn test_steps_cross_words() {
+        // A single open row, wide enough to span three words
+        let mut reach = Reachability::new(150, 1, |_, _| true, (0, 63));
+        reach.step();
+        assert!(reach.frontier.get(0, 62));
+        assert!(reach.frontier.get(0, 64));
+        assert_eq!(reach.count(), 2);
+        reach.step();
+        assert_eq!(reach.count(), 3);
+        assert!(reach.frontier.get(0, 65));
+    }
+
+    #[test]
+    fn test_counts() {
+        // 3x3 room with a wall in the middle
+        let open = |row, col| (row, col) != (1, 1);
+        assert_eq!(
+            Reachability::new(3, 3, open, (0, 0)).counts(4),
+            vec![1, 2, 3, 4, 4]
+        );
+        let open = |_, _| true;
+        assert_eq!(
+            Reachability::new(3, 3, open, (1, 1)).counts(3),
+            vec![1, 4, 5, 4]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::solution::Runnable;
+
+// Kept in day order, `aoc2023 new <day>` inserts new days here
+static SOLUTIONS: &[&dyn Runnable] = &[
+    &crate::day1::Day1,
+    &crate::day2::Day2,
+    &crate::day3::Day3,
+    &crate::day4::Day4,
+    &crate::day5::Day5,
+    &crate::day6::Day6,
+    &crate::day7::Day7,
+    &crate::day8::Day8,
+    &crate::day9::Day9,
+    &crate::day10::Day10,
+    &crate::day21::Day21,
+];
+
+/// Every registered day, ordered by day number.
+pub fn all() -> &'static [&'static dyn Runnable] {
+    SOLUTIONS
+}
+
+pub fn find(day: u8) -> Option<&'static dyn Runnable> {
+    SOLUTIONS.iter().find(|s| s.day() == day).copied()
+}
+
+pub fn latest() -> &'static dyn Runnable {
+    *SOLUTIONS
+        .iter()
+        .max_by_key(|s| s.day())
+        .expect("No solutions registered")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_unique_and_sorted() {
+        let days = all().iter().map(|s| s.day()).collect::<Vec<u8>>();
+        let mut sorted = days.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(days, sorted);
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(21).map(|s| s.day()), Some(21));
+        assert!(find(0).is_none());
+        assert!(find(26).is_none());
+        assert_eq!(latest().day(), 21);
+    }
+
+    #[test]
+    fn test_parsers_accept_examples() {
+        for solution in all() {
+            for example in solution.examples() {
+                if let Some(parsed) = solution.parse(example.input) {
+                    assert_eq!(parsed, Ok(()), "{solution:?}");
+                }
+            }
+        }
+    }
+}
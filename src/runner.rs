@@ -0,0 +1,438 @@
+use std::fmt::Display;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+
+use crate::answer::Answer;
+use crate::answers::{self, AnswerStore, StoreError, Verdict};
+use crate::bench::{self, Baseline, BaselineError, Phase};
+use crate::cli::{InputsCommand, Source, Target};
+use crate::error::Error;
+use crate::inputs::{self, Cache, CacheError, InputError, InputLoader, Status};
+use crate::registry;
+use crate::scaffold::{self, ScaffoldError};
+use crate::solution::{Part, Runnable};
+use crate::transport::{CurlTransport, HttpTransport, Transport};
+
+#[derive(Debug)]
+pub enum RunError {
+    Input(InputError),
+    Cache(CacheError),
+    Scaffold(ScaffoldError),
+    Store(StoreError),
+    Baseline(BaselineError),
+    Solve(u8, Error),
+    NoExample(u8, Part),
+    ExamplesFailed(usize, usize),
+    DaysFailed(usize),
+    VerifyFailed(usize),
+    Regressed(usize),
+}
+impl Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Input(e) => write!(f, "{e}"),
+            RunError::Cache(e) => write!(f, "{e}"),
+            RunError::Scaffold(e) => write!(f, "{e}"),
+            RunError::Store(e) => write!(f, "{e}"),
+            RunError::Baseline(e) => write!(f, "{e}"),
+            RunError::Solve(day, e) => write!(f, "day {day}, {e}"),
+            RunError::NoExample(day, part) => {
+                write!(f, "day {day} has no example for part {part}")
+            }
+            RunError::ExamplesFailed(failed, total) => {
+                write!(f, "{failed} of {total} examples failed")
+            }
+            RunError::DaysFailed(n) => write!(f, "{n} days could not be run"),
+            RunError::VerifyFailed(n) => write!(f, "{n} answers differ from the recorded ones"),
+            RunError::Regressed(n) => write!(f, "{n} timings regressed against the baseline"),
+        }
+    }
+}
+
+fn parts(part: Option<Part>) -> Vec<Part> {
+    match part {
+        Some(p) => vec![p],
+        None => vec![Part::One, Part::Two],
+    }
+}
+
+impl From<InputError> for RunError {
+    fn from(e: InputError) -> Self {
+        RunError::Input(e)
+    }
+}
+
+impl From<CacheError> for RunError {
+    fn from(e: CacheError) -> Self {
+        RunError::Cache(e)
+    }
+}
+
+impl From<ScaffoldError> for RunError {
+    fn from(e: ScaffoldError) -> Self {
+        RunError::Scaffold(e)
+    }
+}
+
+impl From<StoreError> for RunError {
+    fn from(e: StoreError) -> Self {
+        RunError::Store(e)
+    }
+}
+
+impl From<BaselineError> for RunError {
+    fn from(e: BaselineError) -> Self {
+        RunError::Baseline(e)
+    }
+}
+
+/// Reads the input for each of the given parts. Files are only read once, so
+/// that stdin can be shared by both parts, while examples may differ per part.
+fn read_inputs(
+    solution: &dyn Runnable,
+    parts: &[Part],
+    source: &Source,
+    loader: &InputLoader,
+) -> Result<Vec<(Part, String)>, RunError> {
+    let day = solution.day();
+    let input = match source {
+        Source::Default => loader.load(day)?,
+        Source::Path(path) => inputs::read_path(day, path)?,
+        Source::Example => {
+            return parts
+                .iter()
+                .map(|&part| {
+                    solution
+                        .examples()
+                        .iter()
+                        .find(|e| e.part == part)
+                        .map(|e| (part, e.input.to_string()))
+                        .ok_or(RunError::NoExample(day, part))
+                })
+                .collect()
+        }
+    };
+    Ok(parts.iter().map(|&part| (part, input.clone())).collect())
+}
+
+fn print_header(solution: &dyn Runnable) {
+    println!("Day {}: {}", solution.day(), solution.title());
+}
+
+/// Runs the target days. With `record` the answers are stored as confirmed,
+/// for `verify` to compare against later.
+pub fn run(
+    target: Target,
+    part: Option<Part>,
+    record: bool,
+    source: &Source,
+    loader: &InputLoader,
+) -> Result<(), RunError> {
+    let solution = match target {
+        Target::Latest => registry::latest(),
+        Target::Day(s) => s,
+        Target::All => return run_all(part, record, source, loader),
+    };
+
+    let mut store = match record {
+        true => Some(AnswerStore::open(loader.root())?),
+        false => None,
+    };
+    print_header(solution);
+    let mut noted = None;
+    for (part, input) in read_inputs(solution, &parts(part), source, loader)? {
+        // Both parts usually share an input, which only needs noting once
+        if noted.as_ref() != Some(&input) {
+            for note in solution.notes(&input) {
+                println!("Note: {note}");
+            }
+            noted = Some(input.clone());
+        }
+        let answer = solution
+            .solve(part, &input)
+            .map_err(|e| RunError::Solve(solution.day(), e))?;
+        println!("Part {part}: {answer}");
+        if let Some(store) = store.as_mut() {
+            store.record(solution.day(), part, answer);
+        }
+    }
+    if let Some(store) = store {
+        store.save()?;
+        println!("Recorded answers for day {}", solution.day());
+    }
+    Ok(())
+}
+
+fn run_all(
+    part: Option<Part>,
+    record: bool,
+    source: &Source,
+    loader: &InputLoader,
+) -> Result<(), RunError> {
+    let mut failed = 0;
+    for solution in registry::all() {
+        if let Err(e) = run(Target::Day(*solution), part, record, source, loader) {
+            eprintln!("Error: {e}");
+            failed += 1;
+        }
+        println!();
+    }
+    match failed {
+        0 => Ok(()),
+        n => Err(RunError::DaysFailed(n)),
+    }
+}
+
+pub fn list() {
+    for solution in registry::all() {
+        println!("{:>2}  {}", solution.day(), solution.title());
+    }
+}
+
+/// Solves a part, turning a panic into `None` so that one broken day doesn't
+/// stop the others from being checked.
+fn solve_catching(
+    solution: &dyn Runnable,
+    part: Part,
+    input: &str,
+) -> Option<Result<Answer, Error>> {
+    panic::catch_unwind(AssertUnwindSafe(|| solution.solve(part, input))).ok()
+}
+
+/// Runs every example of a day and compares the output with the answer from
+/// the puzzle text.
+pub fn test(solution: &dyn Runnable) -> Result<(), RunError> {
+    print_header(solution);
+    let examples = solution.examples();
+    let mut failed = 0;
+    for (i, example) in examples.iter().enumerate() {
+        let label = format!("Part {}, example {}", example.part, i + 1);
+        match solve_catching(solution, example.part, example.input) {
+            Some(Ok(answer)) if answer == Answer::parse(example.answer) => {
+                println!("{label}: PASS ({answer})")
+            }
+            Some(Ok(answer)) => {
+                println!("{label}: FAIL expected {}, got {answer}", example.answer);
+                failed += 1;
+            }
+            Some(Err(e)) => {
+                println!("{label}: FAIL {e}");
+                failed += 1;
+            }
+            None => {
+                println!("{label}: FAIL panicked");
+                failed += 1;
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        n => Err(RunError::ExamplesFailed(n, examples.len())),
+    }
+}
+
+/// Re-runs every day that has input and compares each part with the
+/// recorded answer.
+pub fn verify(loader: &InputLoader) -> Result<(), RunError> {
+    let store = AnswerStore::open(loader.root())?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solution in registry::all() {
+        let day = solution.day();
+        let input = match loader.load(day) {
+            Ok(input) => input,
+            Err(_) => {
+                println!("Day {day}: MISSING input");
+                missing += 2;
+                continue;
+            }
+        };
+
+        for part in parts(None) {
+            let label = format!("Day {day} part {part}");
+            let actual = match solve_catching(*solution, part, &input) {
+                Some(Ok(actual)) => actual,
+                Some(Err(e)) => {
+                    println!("{label}: FAIL {e}");
+                    failed += 1;
+                    continue;
+                }
+                None => {
+                    println!("{label}: FAIL panicked");
+                    failed += 1;
+                    continue;
+                }
+            };
+            match answers::check(store.get(day, part), actual) {
+                Verdict::Pass => {
+                    println!("{label}: PASS");
+                    passed += 1;
+                }
+                Verdict::Fail { expected, actual } => {
+                    println!("{label}: FAIL {}", answers::diff(&expected, &actual));
+                    failed += 1;
+                }
+                Verdict::Missing => {
+                    println!("{label}: MISSING answer");
+                    missing += 1;
+                }
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    match failed {
+        0 => Ok(()),
+        n => Err(RunError::VerifyFailed(n)),
+    }
+}
+
+/// Times the parser and each part of a day, and compares the medians with
+/// the baseline. With `save_baseline` the medians become the new baseline.
+pub fn bench(
+    solution: &dyn Runnable,
+    part: Option<Part>,
+    options: bench::Options,
+    baseline_path: &Path,
+    save_baseline: bool,
+    source: &Source,
+    loader: &InputLoader,
+) -> Result<(), RunError> {
+    let day = solution.day();
+    let inputs = read_inputs(solution, &parts(part), source, loader)?;
+    let mut baseline = Baseline::load(baseline_path)?;
+
+    let mut phases = vec![];
+    if let Some((_, input)) = inputs.first() {
+        if let Some(parsed) = solution.parse(input) {
+            parsed.map_err(|e| RunError::Solve(day, e))?;
+            phases.push((Phase::Parse, input.as_str(), None));
+        }
+    }
+    let has_parser = phases.iter().any(|&(phase, _, _)| phase == Phase::Parse);
+    for (part, input) in inputs.iter() {
+        // Fail before timing anything, rather than timing the error path
+        solution
+            .solve(*part, input)
+            .map_err(|e| RunError::Solve(day, e))?;
+        let phase = match part {
+            Part::One => Phase::PartOne,
+            Part::Two => Phase::PartTwo,
+        };
+        phases.push((phase, input.as_str(), Some(*part)));
+    }
+
+    print_header(solution);
+    println!(
+        "{} warm-up and {} timed runs",
+        options.warmup, options.iterations
+    );
+    println!("{:<10}{:>12}{:>12}{:>12}", "", "median", "min", "max");
+    if !has_parser {
+        println!(
+            "{:<10}  not timed, each part parses the input itself",
+            "parse"
+        );
+    }
+    let mut regressions = 0;
+    for (phase, input, part) in phases {
+        let stats = bench::measure(options, || match part {
+            Some(part) => {
+                let _ = black_box(solution.solve(part, input));
+            }
+            None => {
+                let _ = black_box(solution.parse(input));
+            }
+        });
+        let Some(stats) = stats else {
+            continue;
+        };
+
+        let comparison = match baseline.get(day, phase) {
+            Some(previous) if bench::is_regression(stats.median, previous) => {
+                regressions += 1;
+                let change = bench::change(stats.median, previous) * 100.0;
+                format!("  {change:+.0}% vs baseline, REGRESSION")
+            }
+            Some(previous) => {
+                let change = bench::change(stats.median, previous) * 100.0;
+                format!("  {change:+.0}% vs baseline")
+            }
+            None => String::new(),
+        };
+        println!(
+            "{:<10}{:>12}{:>12}{:>12}{comparison}",
+            phase.to_string(),
+            bench::format_duration(stats.median),
+            bench::format_duration(stats.min),
+            bench::format_duration(stats.max),
+        );
+        baseline.set(day, phase, stats.median);
+    }
+
+    if save_baseline {
+        baseline.save(baseline_path)?;
+        println!("Saved baseline to {}", baseline_path.display());
+    }
+    match regressions {
+        0 => Ok(()),
+        n => Err(RunError::Regressed(n)),
+    }
+}
+
+/// Environment variable that points fetching at another server, e.g. a local
+/// mirror reachable over plain http.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub fn inputs(command: InputsCommand, loader: &InputLoader) -> Result<(), RunError> {
+    let cache = Cache::new(loader);
+    let registered = registry::all().iter().map(|s| s.day());
+    match command {
+        InputsCommand::List => {
+            for day in registered.clone() {
+                println!("{day:>2}  {}", cache.status(day));
+            }
+            let missing = cache.missing(registered);
+            if !missing.is_empty() {
+                let missing = missing.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+                println!("Missing input for days {}", missing.join(", "));
+            }
+            Ok(())
+        }
+        InputsCommand::Fetch { day, force } => {
+            let days = match day {
+                Some(day) => vec![day],
+                None => cache.missing(registered),
+            };
+            let base_url =
+                std::env::var(BASE_URL_VAR).unwrap_or_else(|_| inputs::BASE_URL.to_string());
+            let transport: &dyn Transport = if base_url.starts_with("http://") {
+                &HttpTransport
+            } else {
+                &CurlTransport
+            };
+            let session = inputs::read_session()?;
+            for day in days {
+                let status = cache.status(day);
+                if !force && matches!(status, Status::Ok | Status::Unrecorded) {
+                    println!("Day {day}: already cached, use --force to download again");
+                    continue;
+                }
+                let path = cache.fetch(day, transport, &base_url, &session)?;
+                println!("Day {day}: saved to {}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn new_day(day: u8, title: Option<&str>, example: Option<&Path>) -> Result<(), RunError> {
+    let example = match example {
+        Some(path) => inputs::read_path(day, path)?,
+        None => String::new(),
+    };
+    for path in scaffold::create(Path::new("src"), day, title.unwrap_or(""), &example)? {
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::error::Error;
+use crate::solution::{Example, Part, Solution};
+
+pub struct Day{day};
+impl Solution for Day{day} {
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    const DAY: u8 = {day};
+    const TITLE: &'static str = {title};
+    const EXAMPLES: &'static [Example] = &[Example {
+        part: Part::One,
+        input: INPUT,
+        // TODO: the answer the puzzle text gives for the example
+        answer: "?",
+    }];
+
+    fn part_one(&self, input: &str) -> Result<i32, Error> {
+        part_one(input)
+    }
+
+    fn part_two(&self, input: &str) -> Result<i32, Error> {
+        part_two(input)
+    }
+}
+
+fn part_one(_input: &str) -> Result<i32, Error> {
+    Ok(0)
+}
+
+fn part_two(_input: &str) -> Result<i32, Error> {
+    Ok(0)
+}
+
+const INPUT: &str = {example};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_one() {
+        let answer = part_one(INPUT).map(|a| a.to_string());
+        assert_eq!(answer.as_deref(), Ok(Day{day}::EXAMPLES[0].answer));
+    }
+}
+"#;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    NotInRepository(PathBuf),
+    AlreadyExists(PathBuf),
+    AlreadyRegistered(u8),
+    MissingRegistry(PathBuf),
+    Io(PathBuf, std::io::Error),
+}
+impl Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaffoldError::NotInRepository(path) => write!(
+                f,
+                "{} not found, run this from the repository root",
+                path.display()
+            ),
+            ScaffoldError::AlreadyExists(path) => {
+                write!(
+                    f,
+                    "{} already exists, refusing to overwrite it",
+                    path.display()
+                )
+            }
+            ScaffoldError::AlreadyRegistered(day) => write!(f, "day {day} is already registered"),
+            ScaffoldError::MissingRegistry(path) => {
+                write!(f, "could not find the solution list in {}", path.display())
+            }
+            ScaffoldError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+        }
+    }
+}
+
+/// Writes `src/dayN.rs` from the template and registers it in `main.rs` and
+/// `registry.rs`. Nothing is touched if the day already exists.
+pub fn create(
+    src: &Path,
+    day: u8,
+    title: &str,
+    example: &str,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let main_path = src.join("main.rs");
+    let registry_path = src.join("registry.rs");
+    let day_path = src.join(format!("day{day}.rs"));
+
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => ScaffoldError::NotInRepository(path.to_path_buf()),
+            _ => ScaffoldError::Io(path.to_path_buf(), e),
+        })
+    };
+    let main = read(&main_path)?;
+    let registry = read(&registry_path)?;
+    if day_path.exists() {
+        return Err(ScaffoldError::AlreadyExists(day_path));
+    }
+
+    let main = register_module(&main, day).ok_or(ScaffoldError::AlreadyRegistered(day))?;
+    let registry = register_solution(&registry, day).map_err(|e| match e {
+        RegisterError::Duplicate => ScaffoldError::AlreadyRegistered(day),
+        RegisterError::NoList => ScaffoldError::MissingRegistry(registry_path.clone()),
+    })?;
+
+    let write = |path: &PathBuf, contents: &str| {
+        std::fs::write(path, contents).map_err(|e| ScaffoldError::Io(path.clone(), e))
+    };
+    write(&day_path, &render(day, title, example))?;
+    write(&main_path, &main)?;
+    write(&registry_path, &registry)?;
+    Ok(vec![day_path, main_path, registry_path])
+}
+
+pub fn render(day: u8, title: &str, example: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &string_literal(title))
+        .replace("{example}", &string_literal(example.trim_end()))
+}
+
+/// Quotes text as a Rust string literal, falling back to a raw string when
+/// the text contains quotes or backslashes.
+fn string_literal(text: &str) -> String {
+    if !text.contains(['"', '\\']) {
+        return format!("\"{text}\"");
+    }
+    let mut hashes = String::from("#");
+    while text.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+    format!("r{hashes}\"{text}\"{hashes}")
+}
+
+/// Adds `mod dayN;` to the module declarations, keeping rustfmt's order.
+/// Returns `None` if the module is already declared.
+fn register_module(main: &str, day: u8) -> Option<String> {
+    let name = format!("day{day}");
+    let new_line = format!("mod {name};");
+    let mut lines = main.lines().collect::<Vec<&str>>();
+    if lines.contains(&new_line.as_str()) {
+        return None;
+    }
+
+    let module = |l: &str| l.strip_prefix("mod ")?.strip_suffix(';').map(String::from);
+    let position = lines
+        .iter()
+        .position(|l| module(l).is_some_and(|m| m > name))
+        .or_else(|| {
+            lines
+                .iter()
+                .rposition(|l| l.starts_with("mod "))
+                .map(|i| i + 1)
+        })
+        .unwrap_or(lines.len());
+    lines.insert(position, &new_line);
+    Some(lines.join("\n") + "\n")
+}
+
+#[derive(Debug, PartialEq)]
+enum RegisterError {
+    Duplicate,
+    NoList,
+}
+
+/// Adds the day to the `SOLUTIONS` list, which is ordered by day number.
+fn register_solution(registry: &str, day: u8) -> Result<String, RegisterError> {
+    let entry_day = |l: &str| -> Option<u8> {
+        l.trim()
+            .strip_prefix("&crate::day")?
+            .split("::")
+            .next()?
+            .parse()
+            .ok()
+    };
+
+    let mut lines = registry.lines().collect::<Vec<&str>>();
+    let start = lines
+        .iter()
+        .position(|l| l.starts_with("static SOLUTIONS"))
+        .ok_or(RegisterError::NoList)?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|l| l.starts_with("];"))
+            .ok_or(RegisterError::NoList)?;
+
+    let mut position = end;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+        match entry_day(line) {
+            Some(d) if d == day => return Err(RegisterError::Duplicate),
+            Some(d) if d > day => {
+                position = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let new_line = format!("    &crate::day{day}::Day{day},");
+    lines.insert(position, &new_line);
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_register_module() {
+        let main =
+            "use std::env;\n\nmod day1;\nmod day10;\nmod day2;\nmod parsing;\n\nfn main() {}\n";
+        assert_eq!(
+            register_module(main, 11).unwrap(),
+            "use std::env;\n\nmod day1;\nmod day10;\nmod day11;\nmod day2;\nmod parsing;\n\nfn main() {}\n"
+        );
+        assert_eq!(register_module(main, 2), None);
+        assert_eq!(
+            register_module("mod day1;\nmod day2;\n", 12).unwrap(),
+            "mod day1;\nmod day12;\nmod day2;\n"
+        );
+    }
+
+    #[test]
+    fn test_register_solution() {
+        let registry = "static SOLUTIONS: &[&dyn Runnable] = &[\n    &crate::day1::Day1,\n    &crate::day21::Day21,\n];\n";
+        assert_eq!(
+            register_solution(registry, 11).unwrap(),
+            "static SOLUTIONS: &[&dyn Runnable] = &[\n    &crate::day1::Day1,\n    &crate::day11::Day11,\n    &crate::day21::Day21,\n];\n"
+        );
+        assert_eq!(
+            register_solution(registry, 22).unwrap(),
+            "static SOLUTIONS: &[&dyn Runnable] = &[\n    &crate::day1::Day1,\n    &crate::day21::Day21,\n    &crate::day22::Day22,\n];\n"
+        );
+        assert_eq!(
+            register_solution(registry, 21),
+            Err(RegisterError::Duplicate)
+        );
+        assert_eq!(register_solution("", 1), Err(RegisterError::NoList));
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(string_literal("a\nb"), "\"a\nb\"");
+        assert_eq!(string_literal("say \"hi\""), "r#\"say \"hi\"\"#");
+        assert_eq!(string_literal("\"#"), "r##\"\"#\"##");
+    }
+
+    #[test]
+    fn test_render() {
+        let source = render(11, "Cosmic Expansion", "...#\n#...\n");
+        assert!(source.contains("pub struct Day11;"));
+        assert!(source.contains("const DAY: u8 = 11;"));
+        assert!(source.contains("const TITLE: &'static str = \"Cosmic Expansion\";"));
+        assert!(source.contains("\nconst INPUT: &str = \"...#\n#...\";\n"));
+        assert!(source.contains("input: INPUT,"));
+        assert!(source.contains("assert_eq!(answer.as_deref(), Ok(Day11::EXAMPLES[0].answer));"));
+        // Anything imported but unused would fail clippy for every new day
+        let imports = source
+            .lines()
+            .filter(|l| l.trim_start().starts_with("use "))
+            .map(str::trim)
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            imports,
+            vec![
+                "use crate::error::Error;",
+                "use crate::solution::{Example, Part, Solution};",
+                "use super::*;"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_refuses_existing_day() {
+        let dir = TempDir::new("scaffold");
+        let src = dir.path();
+        std::fs::write(src.join("main.rs"), "mod day1;\nmod registry;\n").unwrap();
+        std::fs::write(
+            src.join("registry.rs"),
+            "static SOLUTIONS: &[&dyn Runnable] = &[\n    &crate::day1::Day1,\n];\n",
+        )
+        .unwrap();
+        std::fs::write(src.join("day1.rs"), "// solved").unwrap();
+
+        assert!(matches!(
+            create(src, 1, "", ""),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+        assert_eq!(
+            std::fs::read_to_string(src.join("day1.rs")).unwrap(),
+            "// solved"
+        );
+
+        create(src, 2, "Second", "x").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(src.join("main.rs")).unwrap(),
+            "mod day1;\nmod day2;\nmod registry;\n"
+        );
+        assert!(std::fs::read_to_string(src.join("registry.rs"))
+            .unwrap()
+            .contains("    &crate::day2::Day2,\n"));
+        assert!(matches!(
+            create(src, 2, "", ""),
+            Err(ScaffoldError::AlreadyExists(_))
+        ));
+    }
+}
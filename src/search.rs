@@ -0,0 +1,224 @@
+// Day 10 only walks its loop breadth first so far, the weighted searches and
+// path reconstruction wait for the puzzles that need them
+#![cfg_attr(not(test), allow(dead_code))]
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Everything a search from one start node found: how far away each reached
+/// node is, and which node it was reached from.
+#[derive(Debug, Clone)]
+pub struct Search<N> {
+    start: N,
+    dist: HashMap<N, u64>,
+    prev: HashMap<N, N>,
+}
+impl<N: Hash + Eq + Clone> Search<N> {
+    fn new(start: N) -> Self {
+        Self {
+            dist: HashMap::from([(start.clone(), 0)]),
+            prev: HashMap::new(),
+            start,
+        }
+    }
+
+    /// The cost of the cheapest path to `node`, if it was reached.
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.dist.get(node).copied()
+    }
+
+    /// Every reached node with its distance, in no particular order.
+    pub fn distances(&self) -> impl Iterator<Item = (&N, u64)> {
+        self.dist.iter().map(|(n, &d)| (n, d))
+    }
+
+    /// The node `node` was first reached from.
+    pub fn predecessor(&self, node: &N) -> Option<&N> {
+        self.prev.get(node)
+    }
+
+    /// The nodes from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.dist.get(node)?;
+        Some(walk_back(&self.prev, &self.start, node.clone()))
+    }
+}
+
+fn walk_back<N: Hash + Eq + Clone>(prev: &HashMap<N, N>, start: &N, mut node: N) -> Vec<N> {
+    let mut path = vec![node.clone()];
+    while &node != start {
+        node = prev[&node].clone();
+        path.push(node.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth first search, where every step costs 1.
+pub fn bfs<N, F, I>(start: N, mut neighbours: F) -> Search<N>
+where
+    N: Hash + Eq + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((node, dist)) = queue.pop_front() {
+        for next in neighbours(&node) {
+            if let Entry::Vacant(e) = search.dist.entry(next.clone()) {
+                e.insert(dist + 1);
+                search.prev.insert(next.clone(), node.clone());
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm, where `neighbours` gives each next node with the
+/// cost of stepping to it.
+pub fn dijkstra<N, F, I>(start: N, mut neighbours: F) -> Search<N>
+where
+    N: Hash + Ord + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::new(start.clone());
+    let mut heap = BinaryHeap::from([Reverse((0, start))]);
+    while let Some(Reverse((dist, node))) = heap.pop() {
+        if search.dist[&node] < dist {
+            // A cheaper path got here first
+            continue;
+        }
+        for (next, cost) in neighbours(&node) {
+            let next_dist = dist + cost;
+            if search.dist.get(&next).is_none_or(|&d| next_dist < d) {
+                search.dist.insert(next.clone(), next_dist);
+                search.prev.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_dist, next)));
+            }
+        }
+    }
+    search
+}
+
+/// A* search for the cheapest path from `start` to a node matching `goal`.
+/// `heuristic` must never overestimate the remaining cost, or the path found
+/// may not be the cheapest. Returns the path, both ends included, and its
+/// cost.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    mut neighbours: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> Option<(Vec<N>, u64)>
+where
+    N: Hash + Ord + Clone,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: FnMut(&N) -> u64,
+    G: FnMut(&N) -> bool,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut prev = HashMap::new();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0, start.clone()))]);
+    while let Some(Reverse((_, cost, node))) = heap.pop() {
+        if dist[&node] < cost {
+            continue;
+        }
+        if goal(&node) {
+            return Some((walk_back(&prev, &start, node), cost));
+        }
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if dist.get(&next).is_none_or(|&d| next_cost < d) {
+                dist.insert(next.clone(), next_cost);
+                prev.insert(next.clone(), node.clone());
+                heap.push(Reverse((next_cost + heuristic(&next), next_cost, next)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::pos::Pos;
+
+    const MAZE: &str = "S.#.....
+.##.###.
+....#...
+.####.#E";
+
+    fn open_neighbours(grid: &Grid<char>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        grid.neighbours4(pos).filter(|&p| grid[p] != '#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let end = grid.position(|&c| c == 'E').unwrap();
+        let search = bfs(start, |&p| open_neighbours(&grid, p));
+        assert_eq!(search.distance(&start), Some(0));
+        assert_eq!(search.distance(&end), Some(14));
+        assert_eq!(search.distance(&(0, 2)), None);
+        assert_eq!(search.predecessor(&(0, 1)), Some(&start));
+
+        let path = search.path_to(&end).unwrap();
+        assert_eq!(path.len(), 15);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert!(path
+            .windows(2)
+            .all(|w| Pos::from(w[0]).manhattan(w[1].into()) == 1));
+        assert_eq!(search.path_to(&(0, 2)), None);
+    }
+
+    #[test]
+    fn test_bfs_start_not_revisited() {
+        // A cycle back through the start must not give it a new distance
+        let search = bfs(0, |&n| [(n + 1) % 4]);
+        assert_eq!(search.distance(&0), Some(0));
+        assert_eq!(search.distance(&3), Some(3));
+        assert_eq!(search.path_to(&0), Some(vec![0]));
+        assert_eq!(search.distances().count(), 4);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // The direct edge to 3 is dearer than going round through 1 and 2
+        let edges = |&n: &u32| match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 2)],
+            2 => vec![(3, 3)],
+            _ => vec![],
+        };
+        let search = dijkstra(0, edges);
+        assert_eq!(search.distance(&3), Some(6));
+        assert_eq!(search.path_to(&3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(search.distance(&4), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = Grid::parse(MAZE, Some).unwrap();
+        let start = grid.position(|&c| c == 'S').unwrap();
+        let end = grid.position(|&c| c == 'E').unwrap();
+        let neighbours = |&p: &(usize, usize)| {
+            open_neighbours(&grid, p)
+                .into_iter()
+                .map(|p| (p, 1))
+                .collect::<Vec<_>>()
+        };
+        let heuristic = |&p: &(usize, usize)| Pos::from(p).manhattan(end.into());
+        let (path, cost) = astar(start, neighbours, heuristic, |&p| p == end).unwrap();
+        assert_eq!(cost, 14);
+        assert_eq!(path.len(), 15);
+        assert_eq!(astar(start, neighbours, heuristic, |&p| p == (0, 2)), None);
+    }
+}
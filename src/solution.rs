@@ -0,0 +1,98 @@
+use std::fmt::{Debug, Display};
+
+use crate::answer::Answer;
+use crate::error::Error;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "one"),
+            Part::Two => write!(f, "two"),
+        }
+    }
+}
+
+/// An example input from the puzzle text, together with the answer the puzzle
+/// text gives for it, written the way [`Answer`] displays it.
+#[derive(Debug)]
+pub struct Example {
+    pub part: Part,
+    pub input: &'static str,
+    pub answer: &'static str,
+}
+
+/// A single day's puzzle, with the answer type of each part spelled out.
+pub trait Solution {
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    const DAY: u8;
+    const TITLE: &'static str;
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn part_one(&self, input: &str) -> Result<Self::PartOne, Error>;
+    fn part_two(&self, input: &str) -> Result<Self::PartTwo, Error>;
+
+    /// Parses the input without solving anything, so that the parser can be
+    /// timed on its own. `None` for days that only parse as part of solving.
+    fn parse(&self, _input: &str) -> Option<Result<(), Error>> {
+        None
+    }
+
+    /// Remarks about the input worth showing alongside the answers, such as
+    /// parts of it that the puzzle leaves to be read a certain way.
+    fn notes(&self, _input: &str) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Object safe view of a [`Solution`], so that days with different answer
+/// types can live in the same registry.
+pub trait Runnable: Sync {
+    fn day(&self) -> u8;
+    fn title(&self) -> &'static str;
+    fn examples(&self) -> &'static [Example];
+    fn solve(&self, part: Part, input: &str) -> Result<Answer, Error>;
+    fn parse(&self, input: &str) -> Option<Result<(), Error>>;
+    fn notes(&self, input: &str) -> Vec<String>;
+}
+
+impl<S: Solution + Sync> Runnable for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        S::EXAMPLES
+    }
+
+    fn solve(&self, part: Part, input: &str) -> Result<Answer, Error> {
+        match part {
+            Part::One => self.part_one(input).map(Into::into),
+            Part::Two => self.part_two(input).map(Into::into),
+        }
+    }
+
+    fn parse(&self, input: &str) -> Option<Result<(), Error>> {
+        Solution::parse(self, input)
+    }
+
+    fn notes(&self, input: &str) -> Vec<String> {
+        Solution::notes(self, input)
+    }
+}
+
+impl Debug for dyn Runnable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {}", self.day())
+    }
+}
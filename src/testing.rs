@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+
+/// A fresh directory under the system's temporary directory, removed again
+/// when dropped so that failing tests don't leave it behind either.
+pub struct TempDir(PathBuf);
+impl TempDir {
+    /// The name only needs to be unique among the tests, as the process id
+    /// keeps separate test runs apart.
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("aoc2023-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.0.join(path)
+    }
+}
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Answers a single request with the given status and body, and hands back
+/// the request it received.
+pub fn serve_once(status: &str, body: &str) -> (String, std::thread::JoinHandle<String>) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" || line.is_empty() {
+                break;
+            }
+            request.push_str(&line);
+        }
+        stream.write_all(response.as_bytes()).unwrap();
+        request
+    });
+    (base_url, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_dir() {
+        let dir = TempDir::new("testing");
+        let path = dir.path().to_path_buf();
+        std::fs::write(dir.join("file"), "contents").unwrap();
+        assert!(path.join("file").exists());
+        drop(dir);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TransportError {
+    UnsupportedUrl(String),
+    Connection(String),
+    MalformedResponse,
+}
+impl Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransportError::UnsupportedUrl(url) => write!(f, "unsupported url `{url}`"),
+            TransportError::Connection(e) => write!(f, "request failed: {e}"),
+            TransportError::MalformedResponse => write!(f, "malformed http response"),
+        }
+    }
+}
+
+/// Performs a GET request with the Advent of Code session cookie attached.
+pub trait Transport {
+    fn get(&self, url: &str, session: &str) -> Result<Response, TransportError>;
+}
+
+/// Fetches through the `curl` binary, which is what talks to the real site
+/// since the standard library has no TLS. The cookie goes to curl on stdin,
+/// as its arguments can be read by anyone who lists the running processes.
+pub struct CurlTransport;
+impl Transport for CurlTransport {
+    fn get(&self, url: &str, session: &str) -> Result<Response, TransportError> {
+        let connection_error = |e: std::io::Error| TransportError::Connection(e.to_string());
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--compressed"])
+            .args(["--header", "@-"])
+            .args(["--write-out", "\n%{http_code}"])
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(connection_error)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // Dropping stdin closes it, so curl knows the headers are done
+            writeln!(stdin, "Cookie: session={session}").map_err(connection_error)?;
+        }
+        let output = child.wait_with_output().map_err(connection_error)?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(TransportError::Connection(stderr.trim().to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = stdout
+            .rsplit_once('\n')
+            .ok_or(TransportError::MalformedResponse)?;
+        let status = status
+            .parse::<u16>()
+            .map_err(|_| TransportError::MalformedResponse)?;
+        Ok(Response {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
+
+/// Minimal plain `http://` client, for local mirrors and stand-in servers.
+/// Requests are sent as HTTP/1.0 so the server replies without chunking.
+pub struct HttpTransport;
+impl Transport for HttpTransport {
+    fn get(&self, url: &str, session: &str) -> Result<Response, TransportError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| TransportError::UnsupportedUrl(url.to_string()))?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        let address = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:80")
+        };
+
+        let connection_error = |e: std::io::Error| TransportError::Connection(e.to_string());
+        let mut stream = TcpStream::connect(address).map_err(connection_error)?;
+        write!(
+            stream,
+            "GET {path} HTTP/1.0\r\nHost: {host}\r\nCookie: session={session}\r\n\r\n"
+        )
+        .map_err(connection_error)?;
+        let mut response = String::new();
+        stream
+            .read_to_string(&mut response)
+            .map_err(connection_error)?;
+
+        parse_response(&response)
+    }
+}
+
+fn parse_response(response: &str) -> Result<Response, TransportError> {
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or(TransportError::MalformedResponse)?;
+    let status = head
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or(TransportError::MalformedResponse)?;
+    Ok(Response {
+        status,
+        body: body.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::serve_once;
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(
+            parse_response("HTTP/1.1 404 Not Found\r\nServer: x\r\n\r\nmissing"),
+            Ok(Response {
+                status: 404,
+                body: "missing".to_string()
+            })
+        );
+        assert_eq!(
+            parse_response("garbage"),
+            Err(TransportError::MalformedResponse)
+        );
+    }
+
+    #[test]
+    fn test_unsupported_url() {
+        assert_eq!(
+            HttpTransport.get("https://adventofcode.com", ""),
+            Err(TransportError::UnsupportedUrl(
+                "https://adventofcode.com".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_curl() {
+        // Only the standard library is needed to run the tests, so without
+        // curl there's nothing to check
+        let curl = Command::new("curl").arg("--version").output();
+        if !curl.is_ok_and(|output| output.status.success()) {
+            eprintln!("skipping test_curl, no working `curl` on PATH");
+            return;
+        }
+
+        let (base_url, server) = serve_once("200 OK", "1 2 3\n");
+        let response = CurlTransport.get(&format!("{base_url}/day/1/input"), "secret");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2023/day/1/input "));
+        assert!(request.contains("Cookie: session=secret\r\n"));
+        assert_eq!(
+            response,
+            Ok(Response {
+                status: 200,
+                body: "1 2 3\n".to_string()
+            })
+        );
+    }
+}
@@ -1,18 +0,0 @@
-pub fn main() {
-    let input = std::fs::read_to_string("input/dayX").expect("No input");
-    println!("Part one: {}", part_one(&input));
-    // println!("Part two: {}", part_two(&input));
-}
-
-fn part_one(input: &str) -> i32 {
-    0
-}
-
-fn part_two(input: &str) -> i32 {
-    0
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-}